@@ -0,0 +1,78 @@
+use crate::{
+    constraints::{Rules, parse_list},
+    output::Format,
+};
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Shuffle a student roster into groups and write the result as CSV, JSON or Markdown.
+#[derive(Debug, Parser)]
+#[command(name = "csv_grouping", version)]
+pub struct Args {
+    /// Roster to read, with `Name` and `Email` columns.
+    #[arg(short, long, default_value = "students.csv")]
+    pub input: PathBuf,
+
+    /// Where to write the grouped roster.
+    #[arg(short, long, default_value = "grouped_student.csv")]
+    pub output: PathBuf,
+
+    /// Output format. Inferred from the output path when omitted.
+    #[arg(short, long, value_enum)]
+    pub format: Option<Format>,
+
+    /// Where to write roster rows that failed validation.
+    #[arg(long, default_value = "rejected_students.csv")]
+    pub rejects: PathBuf,
+
+    /// Reject students whose names look like duplicates instead of only
+    /// warning about them.
+    #[arg(long)]
+    pub strict_names: bool,
+
+    /// Target number of students per group.
+    #[arg(
+        short = 's',
+        long,
+        default_value_t = 3,
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with = "groups"
+    )]
+    pub group_size: u32,
+
+    /// Exact number of groups to form, instead of a group size.
+    #[arg(short = 'g', long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub groups: Option<u32>,
+
+    /// Seed for the shuffle. The same roster and seed always give the same grouping.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Students (names or emails, comma separated) who must not share a group.
+    /// May be repeated.
+    #[arg(long, value_name = "A,B,...", value_parser = parse_list)]
+    pub apart: Vec<Vec<String>>,
+
+    /// Students (names or emails, comma separated) who must share a group.
+    /// May be repeated.
+    #[arg(long, value_name = "A,B,...", value_parser = parse_list)]
+    pub together: Vec<Vec<String>>,
+
+    /// Roster column, such as `Track`, whose values should be spread evenly.
+    #[arg(long, value_name = "COLUMN")]
+    pub balance: Option<String>,
+
+    /// Earlier CSV grouping output to avoid repeating teammates from. May be repeated.
+    #[arg(long, value_name = "FILE")]
+    pub history: Vec<PathBuf>,
+}
+
+impl Args {
+    pub fn rules(&self) -> Rules {
+        Rules {
+            apart: self.apart.clone(),
+            together: self.together.clone(),
+            balance: self.balance.clone(),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use crate::student::Student;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+};
+
+/// Grouping rules given on the command line, with students referred to by
+/// name or email.
+#[derive(Debug, Default)]
+pub struct Rules {
+    /// Each list holds students who must all end up in different groups.
+    pub apart: Vec<Vec<String>>,
+    /// Each list holds students who must all share one group.
+    pub together: Vec<Vec<String>>,
+    /// Extra column whose values should be mixed evenly across groups.
+    pub balance: Option<String>,
+}
+
+/// The rules resolved against a roster, as student indices.
+#[derive(Debug, Default)]
+pub struct Resolved {
+    /// Students who must share a group. Every student is in exactly one unit.
+    pub units: Vec<Vec<usize>>,
+    /// Unordered pairs of students who must not share a group, smaller index first.
+    pub apart: HashSet<(usize, usize)>,
+    /// Balance column value for each student, if a column was given.
+    pub labels: Option<Vec<String>>,
+    /// How often each pair has been grouped before, smaller index first.
+    pub history: HashMap<(usize, usize), u32>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GroupingError {
+    UnknownStudent(String),
+    AmbiguousStudent(String),
+    UnknownColumn(String),
+    Contradiction { first: String, second: String },
+    ClusterTooLarge { members: Vec<String>, max: usize },
+    Unsatisfiable,
+    SearchLimit,
+}
+
+impl fmt::Display for GroupingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupingError::UnknownStudent(key) => {
+                write!(f, "no student named or emailed `{key}` in the roster")
+            }
+            GroupingError::AmbiguousStudent(key) => {
+                write!(f, "`{key}` matches more than one student, use their email")
+            }
+            GroupingError::UnknownColumn(column) => {
+                write!(f, "roster has no `{column}` column to balance on")
+            }
+            GroupingError::Contradiction { first, second } => write!(
+                f,
+                "{first} and {second} are required to be both together and apart"
+            ),
+            GroupingError::ClusterTooLarge { members, max } => write!(
+                f,
+                "{} must be together but groups hold at most {max} students",
+                members.join(", ")
+            ),
+            GroupingError::Unsatisfiable => {
+                write!(
+                    f,
+                    "no grouping satisfies all of the keep-apart and keep-together rules"
+                )
+            }
+            GroupingError::SearchLimit => write!(
+                f,
+                "gave up looking for a grouping that satisfies all rules, try relaxing them"
+            ),
+        }
+    }
+}
+
+impl Error for GroupingError {}
+
+/// Splits a comma separated rule argument such as `"Ada Obi, Tunde Ade"`.
+pub fn parse_list(value: &str) -> Result<Vec<String>, String> {
+    let names: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if names.len() < 2 {
+        return Err("expected at least two comma separated students".to_string());
+    }
+    Ok(names)
+}
+
+impl Rules {
+    /// Maps every rule onto roster indices. `max_group` is the largest group
+    /// size, used to reject keep-together sets that could never fit.
+    pub fn resolve(
+        &self,
+        students: &[Student],
+        max_group: usize,
+    ) -> Result<Resolved, GroupingError> {
+        let labels = match &self.balance {
+            Some(column) => {
+                if students.iter().any(|s| !s.extra.contains_key(column)) {
+                    return Err(GroupingError::UnknownColumn(column.clone()));
+                }
+                Some(
+                    students
+                        .iter()
+                        .map(|s| s.column(column).to_string())
+                        .collect(),
+                )
+            }
+            None => None,
+        };
+
+        // Union-find over keep-together rules.
+        let mut parent: Vec<usize> = (0..students.len()).collect();
+        for list in &self.together {
+            let ids = lookup_all(students, list)?;
+            for pair in ids.windows(2) {
+                let (a, b) = (find(&mut parent, pair[0]), find(&mut parent, pair[1]));
+                parent[a] = b;
+            }
+        }
+
+        let mut apart = HashSet::new();
+        for list in &self.apart {
+            let ids = lookup_all(students, list)?;
+            for (i, &a) in ids.iter().enumerate() {
+                for &b in &ids[i + 1..] {
+                    if a == b || find(&mut parent, a) == find(&mut parent, b) {
+                        return Err(GroupingError::Contradiction {
+                            first: students[a].name.clone(),
+                            second: students[b].name.clone(),
+                        });
+                    }
+                    apart.insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+
+        // Units keep roster order so a shuffled roster still gives a shuffled result.
+        let mut units: Vec<Vec<usize>> = Vec::new();
+        let mut unit_of_root = vec![usize::MAX; students.len()];
+        for id in 0..students.len() {
+            let root = find(&mut parent, id);
+            if unit_of_root[root] == usize::MAX {
+                unit_of_root[root] = units.len();
+                units.push(Vec::new());
+            }
+            units[unit_of_root[root]].push(id);
+        }
+
+        if let Some(unit) = units.iter().find(|u| u.len() > max_group) {
+            return Err(GroupingError::ClusterTooLarge {
+                members: unit.iter().map(|&id| students[id].name.clone()).collect(),
+                max: max_group,
+            });
+        }
+
+        Ok(Resolved {
+            units,
+            apart,
+            labels,
+            history: HashMap::new(),
+        })
+    }
+}
+
+fn find(parent: &mut [usize], id: usize) -> usize {
+    let mut root = id;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[id] = root;
+    root
+}
+
+fn lookup_all(students: &[Student], keys: &[String]) -> Result<Vec<usize>, GroupingError> {
+    keys.iter().map(|key| lookup(students, key)).collect()
+}
+
+/// Finds a student by email, falling back to a case-insensitive name match.
+fn lookup(students: &[Student], key: &str) -> Result<usize, GroupingError> {
+    if let Some(id) = students
+        .iter()
+        .position(|s| s.email.eq_ignore_ascii_case(key))
+    {
+        return Ok(id);
+    }
+
+    let mut matches = students
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.name.eq_ignore_ascii_case(key));
+    match (matches.next(), matches.next()) {
+        (Some((id, _)), None) => Ok(id),
+        (Some(_), Some(_)) => Err(GroupingError::AmbiguousStudent(key.to_string())),
+        _ => Err(GroupingError::UnknownStudent(key.to_string())),
+    }
+}
@@ -0,0 +1,334 @@
+use crate::constraints::{GroupingError, Resolved};
+use std::collections::{HashMap, HashSet};
+
+/// Upper bound on placements tried before the solver gives up.
+const SEARCH_BUDGET: usize = 200_000;
+
+/// How the caller asked for the roster to be split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupSpec {
+    /// Aim for this many students per group.
+    Size(usize),
+    /// Form exactly this many groups.
+    Count(usize),
+}
+
+/// Works out how many students go into each group.
+///
+/// Remainders are spread one at a time over the first groups, so sizes never
+/// differ by more than one and nobody ends up alone in a trailing chunk.
+pub fn group_sizes(total: usize, spec: GroupSpec) -> Vec<usize> {
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let count = match spec {
+        GroupSpec::Size(size) => total.div_ceil(size.max(1)),
+        GroupSpec::Count(count) => count.clamp(1, total),
+    };
+
+    let base = total / count;
+    let extra = total % count;
+    (0..count)
+        .map(|i| if i < extra { base + 1 } else { base })
+        .collect()
+}
+
+/// Fills groups of the given sizes with student indices, honouring the
+/// resolved keep-apart / keep-together rules and mixing balance labels.
+///
+/// Units are placed most-constrained first into the group that keeps the
+/// balance column most even and repeats the fewest past pairings; dead ends
+/// are backtracked out of. The result is then refined by swapping students
+/// between groups while that lowers the number of repeat pairings.
+pub fn solve(sizes: &[usize], rules: &Resolved) -> Result<Vec<Vec<usize>>, GroupingError> {
+    let mut order: Vec<usize> = (0..rules.units.len()).collect();
+    order.sort_by_key(|&u| {
+        let unit = &rules.units[u];
+        let conflicts = rules
+            .apart
+            .iter()
+            .filter(|(a, b)| unit.contains(a) || unit.contains(b))
+            .count();
+        std::cmp::Reverse((unit.len(), conflicts))
+    });
+
+    let mut solver = Solver {
+        rules,
+        order,
+        members: vec![Vec::new(); sizes.len()],
+        remaining: sizes.to_vec(),
+        label_counts: vec![HashMap::new(); sizes.len()],
+        budget: SEARCH_BUDGET,
+    };
+
+    match solver.place(0) {
+        true => {}
+        false if solver.budget == 0 => return Err(GroupingError::SearchLimit),
+        false => return Err(GroupingError::Unsatisfiable),
+    }
+
+    let mut groups = solver.members;
+    if !rules.history.is_empty() {
+        reduce_repeats(&mut groups, rules);
+    }
+    Ok(groups)
+}
+
+/// Hill-climbs by swapping pairs of unconstrained students across groups.
+///
+/// Only students outside keep-together sets who carry the same balance label
+/// are swapped, so every rule and the label mix stay intact.
+fn reduce_repeats(groups: &mut [Vec<usize>], rules: &Resolved) {
+    let clustered: HashSet<usize> = rules
+        .units
+        .iter()
+        .filter(|unit| unit.len() > 1)
+        .flatten()
+        .copied()
+        .collect();
+    let same_label = |a: usize, b: usize| match &rules.labels {
+        Some(labels) => labels[a] == labels[b],
+        None => true,
+    };
+    let cost = |student: usize, group: &[usize], skip: usize| -> i64 {
+        group
+            .iter()
+            .filter(|&&m| m != student && m != skip)
+            .map(|&m| i64::from(pair_count(&rules.history, student, m)))
+            .sum()
+    };
+    let allowed = |student: usize, group: &[usize], skip: usize| {
+        group
+            .iter()
+            .filter(|&&m| m != skip)
+            .all(|&m| !rules.apart.contains(&(student.min(m), student.max(m))))
+    };
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for g1 in 0..groups.len() {
+            for g2 in g1 + 1..groups.len() {
+                for i in 0..groups[g1].len() {
+                    for j in 0..groups[g2].len() {
+                        let (a, b) = (groups[g1][i], groups[g2][j]);
+                        if clustered.contains(&a) || clustered.contains(&b) || !same_label(a, b) {
+                            continue;
+                        }
+                        if !allowed(b, &groups[g1], a) || !allowed(a, &groups[g2], b) {
+                            continue;
+                        }
+
+                        let before = cost(a, &groups[g1], a) + cost(b, &groups[g2], b);
+                        let after = cost(b, &groups[g1], a) + cost(a, &groups[g2], b);
+                        if after < before {
+                            groups[g1][i] = b;
+                            groups[g2][j] = a;
+                            improved = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn pair_count(history: &HashMap<(usize, usize), u32>, a: usize, b: usize) -> u32 {
+    history.get(&(a.min(b), a.max(b))).copied().unwrap_or(0)
+}
+
+struct Solver<'a> {
+    rules: &'a Resolved,
+    order: Vec<usize>,
+    members: Vec<Vec<usize>>,
+    remaining: Vec<usize>,
+    label_counts: Vec<HashMap<&'a str, usize>>,
+    budget: usize,
+}
+
+impl<'a> Solver<'a> {
+    fn place(&mut self, next: usize) -> bool {
+        let Some(&unit_id) = self.order.get(next) else {
+            return true;
+        };
+        if self.budget == 0 {
+            return false;
+        }
+        self.budget -= 1;
+
+        let unit = &self.rules.units[unit_id];
+        let mut candidates: Vec<usize> = (0..self.members.len())
+            .filter(|&g| self.remaining[g] >= unit.len() && !self.conflicts(unit, g))
+            .collect();
+        candidates.sort_by_key(|&g| (self.penalty(unit, g), std::cmp::Reverse(self.remaining[g])));
+
+        for g in candidates {
+            self.assign(unit, g);
+            if self.place(next + 1) {
+                return true;
+            }
+            self.unassign(unit, g);
+            if self.budget == 0 {
+                return false;
+            }
+        }
+        false
+    }
+
+    fn conflicts(&self, unit: &[usize], group: usize) -> bool {
+        unit.iter().any(|&a| {
+            self.members[group]
+                .iter()
+                .any(|&b| self.rules.apart.contains(&(a.min(b), a.max(b))))
+        })
+    }
+
+    /// Ranks `group` for the unit: students already there sharing a balance
+    /// label first, then past pairings with the students already there.
+    fn penalty(&self, unit: &[usize], group: usize) -> (usize, u32) {
+        let balance = match &self.rules.labels {
+            Some(labels) => unit
+                .iter()
+                .map(|&id| {
+                    self.label_counts[group]
+                        .get(labels[id].as_str())
+                        .copied()
+                        .unwrap_or(0)
+                })
+                .sum(),
+            None => 0,
+        };
+        let repeats = unit
+            .iter()
+            .flat_map(|&a| {
+                self.members[group]
+                    .iter()
+                    .map(move |&b| pair_count(&self.rules.history, a, b))
+            })
+            .sum();
+        (balance, repeats)
+    }
+
+    fn assign(&mut self, unit: &[usize], group: usize) {
+        self.members[group].extend_from_slice(unit);
+        self.remaining[group] -= unit.len();
+        if let Some(labels) = &self.rules.labels {
+            for &id in unit {
+                *self.label_counts[group]
+                    .entry(labels[id].as_str())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    fn unassign(&mut self, unit: &[usize], group: usize) {
+        let kept = self.members[group].len() - unit.len();
+        self.members[group].truncate(kept);
+        self.remaining[group] += unit.len();
+        if let Some(labels) = &self.rules.labels {
+            for &id in unit {
+                if let Some(count) = self.label_counts[group].get_mut(labels[id].as_str()) {
+                    *count -= 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::overlap;
+
+    fn singles(n: usize) -> Resolved {
+        Resolved {
+            units: (0..n).map(|id| vec![id]).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn group_of(groups: &[Vec<usize>], id: usize) -> usize {
+        groups.iter().position(|g| g.contains(&id)).unwrap()
+    }
+
+    #[test]
+    fn size_spreads_remainder_instead_of_orphan() {
+        assert_eq!(group_sizes(7, GroupSpec::Size(3)), vec![3, 2, 2]);
+        assert_eq!(group_sizes(10, GroupSpec::Size(3)), vec![3, 3, 2, 2]);
+        assert_eq!(group_sizes(9, GroupSpec::Size(3)), vec![3, 3, 3]);
+    }
+
+    #[test]
+    fn count_is_clamped_to_roster() {
+        assert_eq!(group_sizes(5, GroupSpec::Count(2)), vec![3, 2]);
+        assert_eq!(group_sizes(3, GroupSpec::Count(10)), vec![1, 1, 1]);
+        assert!(group_sizes(0, GroupSpec::Count(4)).is_empty());
+    }
+
+    #[test]
+    fn solve_fills_every_group_exactly() {
+        let groups = solve(&[3, 2, 2], &singles(7)).unwrap();
+        let sizes: Vec<usize> = groups.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![3, 2, 2]);
+    }
+
+    #[test]
+    fn solve_respects_apart_and_together() {
+        let mut rules = singles(6);
+        rules.units = vec![vec![0, 1], vec![2], vec![3], vec![4], vec![5]];
+        rules.apart = HashSet::from([(0, 2), (2, 3), (3, 4)]);
+
+        let groups = solve(&[3, 3], &rules).unwrap();
+        assert_eq!(group_of(&groups, 0), group_of(&groups, 1));
+        for &(a, b) in &rules.apart {
+            assert_ne!(group_of(&groups, a), group_of(&groups, b));
+        }
+    }
+
+    #[test]
+    fn solve_mixes_balance_labels() {
+        let mut rules = singles(6);
+        rules.labels = Some(
+            [
+                "Stylus", "Stylus", "Stylus", "Soroban", "Soroban", "Soroban",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+
+        let groups = solve(&[2, 2, 2], &rules).unwrap();
+        for group in &groups {
+            let labels = rules.labels.as_ref().unwrap();
+            assert_ne!(labels[group[0]], labels[group[1]]);
+        }
+    }
+
+    #[test]
+    fn solve_avoids_previous_teammates() {
+        // Last round was {0,1,2} and {3,4,5}; a fresh split of 6 into pairs
+        // never needs to repeat any of those.
+        let mut rules = singles(6);
+        for group in [[0, 1, 2], [3, 4, 5]] {
+            for (i, &a) in group.iter().enumerate() {
+                for &b in &group[i + 1..] {
+                    rules.history.insert((a, b), 1);
+                }
+            }
+        }
+
+        let groups = solve(&[2, 2, 2], &rules).unwrap();
+        assert_eq!(overlap(&groups, &rules.history).repeated, 0);
+    }
+
+    #[test]
+    fn solve_reports_impossible_rules() {
+        let mut rules = singles(3);
+        rules.apart = HashSet::from([(0, 1), (0, 2), (1, 2)]);
+        assert_eq!(solve(&[2, 1], &rules), Err(GroupingError::Unsatisfiable));
+
+        let mut rules = singles(4);
+        rules.units = vec![vec![0, 1, 2], vec![3]];
+        assert_eq!(solve(&[2, 2], &rules), Err(GroupingError::Unsatisfiable));
+    }
+}
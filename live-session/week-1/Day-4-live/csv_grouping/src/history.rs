@@ -0,0 +1,137 @@
+use crate::student::Student;
+use csv::ReaderBuilder;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Deserialize)]
+struct GroupedRow {
+    #[serde(rename = "Group")]
+    group: String,
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Email")]
+    email: String,
+}
+
+/// How often each pair of students has shared a group in earlier rounds,
+/// keyed by their lowercased emails (names when an email is missing).
+#[derive(Debug, Default)]
+pub struct History {
+    pairs: HashMap<(String, String), u32>,
+}
+
+impl History {
+    /// Reads previous `grouped_student.csv` outputs.
+    pub fn read(paths: &[PathBuf]) -> Result<Self, String> {
+        let mut history = History::default();
+        for path in paths {
+            history
+                .add_file(path)
+                .map_err(|err| format!("could not read history {}: {err}", path.display()))?;
+        }
+        Ok(history)
+    }
+
+    fn add_file(&mut self, path: &Path) -> csv::Result<()> {
+        let mut reader = ReaderBuilder::new().has_headers(true).from_path(path)?;
+
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for row in reader.deserialize() {
+            let row: GroupedRow = row?;
+            groups
+                .entry(row.group)
+                .or_default()
+                .push(key(&row.name, &row.email));
+        }
+
+        for members in groups.values() {
+            for (i, a) in members.iter().enumerate() {
+                for b in &members[i + 1..] {
+                    *self.pairs.entry(ordered(a.clone(), b.clone())).or_default() += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Past pairings between students on the current roster, as index pairs
+    /// with the smaller index first.
+    pub fn resolve(&self, students: &[Student]) -> HashMap<(usize, usize), u32> {
+        let index: HashMap<String, usize> = students
+            .iter()
+            .enumerate()
+            .map(|(id, s)| (key(&s.name, &s.email), id))
+            .collect();
+
+        self.pairs
+            .iter()
+            .filter_map(|((a, b), &count)| {
+                let (a, b) = (*index.get(a)?, *index.get(b)?);
+                Some(((a.min(b), a.max(b)), count))
+            })
+            .collect()
+    }
+}
+
+fn key(name: &str, email: &str) -> String {
+    let email = email.trim();
+    if email.is_empty() {
+        name.trim().to_lowercase()
+    } else {
+        email.to_lowercase()
+    }
+}
+
+fn ordered(a: String, b: String) -> (String, String) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+/// How much a grouping repeats earlier pairings.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Overlap {
+    /// Teammate pairs in the new grouping.
+    pub pairs: usize,
+    /// Of those, pairs who have worked together before.
+    pub repeated: usize,
+    /// Sum of how many times each repeated pair has met before.
+    pub score: u32,
+}
+
+pub fn overlap(groups: &[Vec<usize>], history: &HashMap<(usize, usize), u32>) -> Overlap {
+    let mut result = Overlap::default();
+    for group in groups {
+        for (i, &a) in group.iter().enumerate() {
+            for &b in &group[i + 1..] {
+                result.pairs += 1;
+                if let Some(&count) = history.get(&(a.min(b), a.max(b))) {
+                    result.repeated += 1;
+                    result.score += count;
+                }
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlap_counts_repeat_pairs() {
+        let history = HashMap::from([((0, 1), 2), ((2, 3), 1)]);
+        let groups = vec![vec![1, 0, 4], vec![2, 5]];
+
+        assert_eq!(
+            overlap(&groups, &history),
+            Overlap {
+                pairs: 4,
+                repeated: 1,
+                score: 2,
+            }
+        );
+    }
+}
@@ -1,38 +1,89 @@
-use csv::{ReaderBuilder, Result, WriterBuilder};
-use rand::{seq::SliceRandom, thread_rng};
-use serde::Deserialize;
-use std::fs::File;
-
-#[derive(Debug, Deserialize)]
-struct Student {
-    #[serde(rename = "Name")]
-    name: String,
-    #[serde(rename = "Email")]
-    email: String,
+mod cli;
+mod constraints;
+mod grouping;
+mod history;
+mod output;
+mod student;
+mod validate;
+
+use clap::Parser;
+use cli::Args;
+use grouping::{GroupSpec, group_sizes, solve};
+use history::{History, overlap};
+use output::{Format, Group};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom, thread_rng};
+use std::{error::Error, process};
+use student::Roster;
+use validate::{validate, write_rejects};
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(&args) {
+        eprintln!("error: {err}");
+        process::exit(1);
+    }
 }
-fn main() -> Result<()> {
-    let file = File::open("students.csv")?;
-    let mut reader_builder = ReaderBuilder::new().has_headers(true).from_reader(file);
 
-    let mut students: Vec<Student> = reader_builder.deserialize().collect::<Result<_>>()?;
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let Roster {
+        extra_columns,
+        students,
+        mut rejects,
+    } = Roster::read(&args.input)
+        .map_err(|err| format!("could not read {}: {err}", args.input.display()))?;
 
-    let grouped_csv = File::create("grouped_student.csv")?;
-    let mut writer_builder = WriterBuilder::new()
-        .has_headers(true)
-        .from_writer(grouped_csv);
+    let (mut students, invalid, warnings) = validate(students, args.strict_names);
+    rejects.extend(invalid);
+    rejects.sort_by_key(|r| r.row);
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+    if !rejects.is_empty() {
+        write_rejects(&args.rejects, &rejects)?;
+        eprintln!(
+            "Skipped {} invalid rows, see {}",
+            rejects.len(),
+            args.rejects.display()
+        );
+    }
 
-    writer_builder.write_record(["Group", "Name", "Email"])?;
+    // Always shuffle from a known seed so a published grouping can be rebuilt.
+    let seed = args.seed.unwrap_or_else(|| thread_rng().r#gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+    students.shuffle(&mut rng);
 
-    let mut rand = thread_rng();
-    students.shuffle(&mut rand);
+    let spec = match args.groups {
+        Some(count) => GroupSpec::Count(count as usize),
+        None => GroupSpec::Size(args.group_size as usize),
+    };
+    let sizes = group_sizes(students.len(), spec);
+    let max_group = sizes.iter().copied().max().unwrap_or(0);
+    let mut rules = args.rules().resolve(&students, max_group)?;
+    rules.history = History::read(&args.history)?.resolve(&students);
+    let groups = solve(&sizes, &rules)?;
 
-    for (i, chunk) in students.chunks(3).enumerate() {
-        let group_name = format!("Group {}", i + 1);
-        for student in chunk {
-            writer_builder.write_record([&group_name, &student.name, &student.email])?;
-        }
-    }
+    let named: Vec<Group> = groups
+        .iter()
+        .enumerate()
+        .map(|(i, group)| Group {
+            name: format!("Group {}", i + 1),
+            members: group.iter().map(|&id| &students[id]).collect(),
+        })
+        .collect();
+    let format = args.format.unwrap_or_else(|| Format::infer(&args.output));
+    output::write(format, &args.output, &named, &extra_columns)?;
 
-    writer_builder.flush()?;
+    println!(
+        "Wrote {} groups to {} (seed {seed})",
+        groups.len(),
+        args.output.display()
+    );
+    if !args.history.is_empty() {
+        let overlap = overlap(&groups, &rules.history);
+        println!(
+            "Pairing overlap: {} of {} teammate pairs worked together before (score {})",
+            overlap.repeated, overlap.pairs, overlap.score
+        );
+    }
     Ok(())
 }
@@ -0,0 +1,206 @@
+use crate::student::Student;
+use clap::ValueEnum;
+use csv::WriterBuilder;
+use serde::Serialize;
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// One `Group,Name,Email,...` CSV file.
+    Csv,
+    /// A JSON array of groups with their members.
+    Json,
+    /// A Markdown section and table per group, ready for GitHub or Discord.
+    Markdown,
+    /// A directory with one CSV file per group.
+    PerGroup,
+}
+
+impl Format {
+    /// Picks a format from the output path: `.json`, `.md`, a directory or a
+    /// path ending in `/` for per-group files, and CSV otherwise.
+    pub fn infer(path: &Path) -> Format {
+        if path.is_dir() || path.to_string_lossy().ends_with(['/', '\\']) {
+            return Format::PerGroup;
+        }
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Format::Json,
+            Some(ext) if ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown") => {
+                Format::Markdown
+            }
+            _ => Format::Csv,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Group<'a> {
+    pub name: String,
+    pub members: Vec<&'a Student>,
+}
+
+/// Writes `groups` to `path` in the given format. `extra_columns` fixes the
+/// order of any roster columns beyond name and email.
+pub fn write(
+    format: Format,
+    path: &Path,
+    groups: &[Group],
+    extra_columns: &[String],
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        Format::Csv => write_csv(File::create(path)?, groups, extra_columns, true)?,
+        Format::Json => {
+            let mut file = File::create(path)?;
+            serde_json::to_writer_pretty(&mut file, groups)?;
+            writeln!(file)?;
+        }
+        Format::Markdown => write_markdown(File::create(path)?, groups, extra_columns)?,
+        Format::PerGroup => {
+            fs::create_dir_all(path)?;
+            for group in groups {
+                let file = File::create(group_file(path, &group.name))?;
+                write_csv(file, std::slice::from_ref(group), extra_columns, false)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `Group 1` becomes `<dir>/group_1.csv`.
+fn group_file(dir: &Path, group: &str) -> PathBuf {
+    dir.join(format!("{}.csv", group.to_lowercase().replace(' ', "_")))
+}
+
+fn write_csv<W: Write>(
+    out: W,
+    groups: &[Group],
+    extra_columns: &[String],
+    with_group: bool,
+) -> csv::Result<()> {
+    let mut writer_builder = WriterBuilder::new().has_headers(true).from_writer(out);
+
+    let mut header = if with_group { vec!["Group"] } else { vec![] };
+    header.extend(["Name", "Email"]);
+    header.extend(extra_columns.iter().map(String::as_str));
+    writer_builder.write_record(&header)?;
+
+    for group in groups {
+        for student in &group.members {
+            let mut record = if with_group {
+                vec![group.name.as_str()]
+            } else {
+                vec![]
+            };
+            record.extend([student.name.as_str(), &student.email]);
+            record.extend(extra_columns.iter().map(|c| student.column(c)));
+            writer_builder.write_record(&record)?;
+        }
+    }
+
+    writer_builder.flush()?;
+    Ok(())
+}
+
+fn write_markdown<W: Write>(
+    mut out: W,
+    groups: &[Group],
+    extra_columns: &[String],
+) -> io::Result<()> {
+    let mut header = vec!["Name", "Email"];
+    header.extend(extra_columns.iter().map(String::as_str));
+
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "## {}", group.name)?;
+        writeln!(out)?;
+        writeln!(out, "| {} |", header.join(" | "))?;
+        writeln!(out, "|{}", " --- |".repeat(header.len()))?;
+        for student in &group.members {
+            let mut cells = vec![student.name.as_str(), &student.email];
+            cells.extend(extra_columns.iter().map(|c| student.column(c)));
+            let cells: Vec<String> = cells.iter().map(|c| c.replace('|', "\\|")).collect();
+            writeln!(out, "| {} |", cells.join(" | "))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student(name: &str, email: &str, track: &str) -> Student {
+        Student {
+            name: name.to_string(),
+            email: email.to_string(),
+            extra: [("Track".to_string(), track.to_string())].into(),
+            row: 0,
+        }
+    }
+
+    #[test]
+    fn infers_format_from_extension() {
+        assert_eq!(Format::infer(Path::new("groups.json")), Format::Json);
+        assert_eq!(Format::infer(Path::new("groups.MD")), Format::Markdown);
+        assert_eq!(Format::infer(Path::new("groups/")), Format::PerGroup);
+        assert_eq!(Format::infer(Path::new("grouped_student.csv")), Format::Csv);
+    }
+
+    #[test]
+    fn markdown_has_a_table_per_group() {
+        let (a, b) = (
+            student("Ada | Obi", "ada@example.com", "Stylus"),
+            student("Tunde", "tunde@example.com", "Soroban"),
+        );
+        let groups = [
+            Group {
+                name: "Group 1".to_string(),
+                members: vec![&a],
+            },
+            Group {
+                name: "Group 2".to_string(),
+                members: vec![&b],
+            },
+        ];
+
+        let mut out = Vec::new();
+        write_markdown(&mut out, &groups, &["Track".to_string()]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "## Group 1\n\n\
+             | Name | Email | Track |\n\
+             | --- | --- | --- |\n\
+             | Ada \\| Obi | ada@example.com | Stylus |\n\
+             \n\
+             ## Group 2\n\n\
+             | Name | Email | Track |\n\
+             | --- | --- | --- |\n\
+             | Tunde | tunde@example.com | Soroban |\n"
+        );
+    }
+
+    #[test]
+    fn json_uses_roster_headers() {
+        let a = student("Ada", "ada@example.com", "Stylus");
+        let groups = [Group {
+            name: "Group 1".to_string(),
+            members: vec![&a],
+        }];
+
+        let json = serde_json::to_value(groups).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "name": "Group 1",
+                "members": [{ "Name": "Ada", "Email": "ada@example.com", "Track": "Stylus" }]
+            }])
+        );
+    }
+}
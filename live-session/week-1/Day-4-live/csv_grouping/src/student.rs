@@ -0,0 +1,134 @@
+use crate::validate::Reject;
+use csv::{ErrorKind, ReaderBuilder, StringRecord, Trim};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error, path::Path};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Student {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Email")]
+    pub email: String,
+    /// Every other column in the roster (track, level, ...), keyed by header.
+    #[serde(skip_deserializing, flatten)]
+    pub extra: BTreeMap<String, String>,
+    /// Line of the roster file the student was read from.
+    #[serde(skip)]
+    pub row: u64,
+}
+
+impl Student {
+    /// Value of an extra column, or an empty string when the cell is missing.
+    pub fn column(&self, name: &str) -> &str {
+        self.extra.get(name).map(String::as_str).unwrap_or("")
+    }
+}
+
+/// The students in a roster file plus the extra columns it carried, in file order.
+#[derive(Debug)]
+pub struct Roster {
+    pub extra_columns: Vec<String>,
+    pub students: Vec<Student>,
+    /// Rows that could not be read as a student at all.
+    pub rejects: Vec<Reject>,
+}
+
+impl Roster {
+    /// Reads a roster with every cell trimmed. Rows that don't parse are
+    /// collected in `rejects` instead of failing the whole file.
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut reader = ReaderBuilder::new()
+            .has_headers(true)
+            .flexible(true)
+            .trim(Trim::All)
+            .from_path(path)?;
+        let headers = reader.headers()?.clone();
+
+        let position = |column: &str| {
+            headers
+                .iter()
+                .position(|h| h == column)
+                .ok_or_else(|| format!("missing `{column}` column"))
+        };
+        let (name_at, email_at) = (position("Name")?, position("Email")?);
+
+        let extra_columns = headers
+            .iter()
+            .filter(|h| !is_core_column(h))
+            .map(str::to_string)
+            .collect();
+
+        let mut students = Vec::new();
+        let mut rejects = Vec::new();
+        for result in reader.records() {
+            let mut record = match result {
+                Ok(record) => record,
+                Err(err) => match err.kind() {
+                    ErrorKind::Utf8 { pos, .. } => {
+                        rejects.push(Reject {
+                            row: pos.as_ref().map_or(0, |p| p.line()),
+                            name: String::new(),
+                            email: String::new(),
+                            reason: "row is not valid UTF-8".to_string(),
+                        });
+                        continue;
+                    }
+                    _ => return Err(err.into()),
+                },
+            };
+            let row = record.position().map_or(0, |p| p.line());
+            let reject = |record: &StringRecord, reason: String| Reject {
+                row,
+                name: record.get(name_at).unwrap_or_default().to_string(),
+                email: record.get(email_at).unwrap_or_default().to_string(),
+                reason,
+            };
+
+            // An extra cell usually means an unquoted comma inside a name.
+            if record.len() > headers.len() {
+                let reason = format!(
+                    "has {} cells but the header has {}",
+                    record.len(),
+                    headers.len()
+                );
+                rejects.push(reject(&record, reason));
+                continue;
+            }
+            // Short rows just leave the trailing columns empty.
+            while record.len() < headers.len() {
+                record.push_field("");
+            }
+
+            match student_from_record(&headers, &record) {
+                Ok(mut student) => {
+                    student.row = row;
+                    students.push(student);
+                }
+                Err(err) => rejects.push(reject(&record, format!("malformed row: {err}"))),
+            }
+        }
+
+        Ok(Roster {
+            extra_columns,
+            students,
+            rejects,
+        })
+    }
+}
+
+fn is_core_column(header: &str) -> bool {
+    header == "Name" || header == "Email"
+}
+
+// Extra cells are copied as raw text: letting serde infer them would turn a
+// level like `007` into the integer 7.
+fn student_from_record(headers: &StringRecord, record: &StringRecord) -> csv::Result<Student> {
+    let mut student: Student = record.deserialize(Some(headers))?;
+    student.extra = headers
+        .iter()
+        .zip(record.iter())
+        .filter(|(h, _)| !is_core_column(h))
+        .map(|(h, v)| (h.to_string(), v.to_string()))
+        .collect();
+    Ok(student)
+}
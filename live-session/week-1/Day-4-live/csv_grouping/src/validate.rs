@@ -0,0 +1,212 @@
+use crate::student::Student;
+use csv::WriterBuilder;
+use std::{collections::HashMap, path::Path};
+
+/// A roster row left out of the grouping, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reject {
+    pub row: u64,
+    pub name: String,
+    pub email: String,
+    pub reason: String,
+}
+
+/// Splits students into clean rows and rejects.
+///
+/// Rows need a name and a well-formed email. The first row with a given email
+/// wins and later copies are rejected. Names that only differ by case,
+/// punctuation or a single typo are reported as warnings, since two students
+/// can share a name; with `strict_names` they are rejected too.
+pub fn validate(
+    students: Vec<Student>,
+    strict_names: bool,
+) -> (Vec<Student>, Vec<Reject>, Vec<String>) {
+    let mut clean: Vec<Student> = Vec::new();
+    let mut rejects = Vec::new();
+    let mut warnings = Vec::new();
+    let mut seen_emails: HashMap<String, u64> = HashMap::new();
+
+    for student in students {
+        let reject = |reason: String| Reject {
+            row: student.row,
+            name: student.name.clone(),
+            email: student.email.clone(),
+            reason,
+        };
+
+        if student.name.is_empty() {
+            rejects.push(reject("missing name".to_string()));
+            continue;
+        }
+        if student.email.is_empty() {
+            rejects.push(reject("missing email".to_string()));
+            continue;
+        }
+        if !is_valid_email(&student.email) {
+            rejects.push(reject(format!("invalid email `{}`", student.email)));
+            continue;
+        }
+
+        let email = student.email.to_lowercase();
+        if let Some(first) = seen_emails.get(&email) {
+            rejects.push(reject(format!("duplicate email of row {first}")));
+            continue;
+        }
+
+        let name = normalize_name(&student.name);
+        if let Some(other) = clean
+            .iter()
+            .find(|other| is_near_duplicate(&name, &normalize_name(&other.name)))
+        {
+            let reason = format!(
+                "name looks like a duplicate of row {} ({})",
+                other.row, other.name
+            );
+            if strict_names {
+                rejects.push(reject(reason));
+                continue;
+            }
+            warnings.push(format!("row {}: {reason}", student.row));
+        }
+
+        seen_emails.insert(email, student.row);
+        clean.push(student);
+    }
+
+    (clean, rejects, warnings)
+}
+
+pub fn write_rejects(path: &Path, rejects: &[Reject]) -> csv::Result<()> {
+    let mut writer = WriterBuilder::new().has_headers(true).from_path(path)?;
+    writer.write_record(["Row", "Name", "Email", "Reason"])?;
+    for reject in rejects {
+        writer.write_record([
+            reject.row.to_string().as_str(),
+            &reject.name,
+            &reject.email,
+            &reject.reason,
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// A deliberately small syntax check: `local@domain.tld` with no spaces.
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !email.chars().any(char::is_whitespace)
+        && domain.contains('.')
+        && domain.split('.').all(|part| !part.is_empty())
+}
+
+/// Lowercases, drops punctuation and collapses whitespace.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn is_near_duplicate(a: &str, b: &str) -> bool {
+    // One typo in a short name is usually a different person ("Ada" / "Ade").
+    a == b || (a.chars().count().min(b.chars().count()) >= 8 && edit_distance(a, b) <= 1)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != cb);
+            curr[j + 1] = substitute.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student(row: u64, name: &str, email: &str) -> Student {
+        Student {
+            name: name.to_string(),
+            email: email.to_string(),
+            extra: Default::default(),
+            row,
+        }
+    }
+
+    #[test]
+    fn email_syntax() {
+        assert!(is_valid_email("liam.johnson@example.com"));
+        assert!(!is_valid_email("liam.johnson@example"));
+        assert!(!is_valid_email("@example.com"));
+        assert!(!is_valid_email("liam johnson@example.com"));
+        assert!(!is_valid_email("liam@@example.com"));
+        assert!(!is_valid_email("liam@example..com"));
+    }
+
+    #[test]
+    fn rejects_bad_and_duplicate_rows() {
+        let (clean, rejects, _) = validate(
+            vec![
+                student(2, "Liam Johnson", "liam@example.com"),
+                student(3, "Emma Smith", "not-an-email"),
+                student(4, "Noah Williams", "LIAM@example.com"),
+                student(5, "liam  johnson.", "liam2@example.com"),
+                student(6, "Liam Jonson", "liam3@example.com"),
+                student(7, "", "nobody@example.com"),
+                student(8, "Olivia Brown", "olivia@example.com"),
+            ],
+            true,
+        );
+
+        let kept: Vec<u64> = clean.iter().map(|s| s.row).collect();
+        assert_eq!(kept, vec![2, 8]);
+
+        let reasons: Vec<(u64, &str)> =
+            rejects.iter().map(|r| (r.row, r.reason.as_str())).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (3, "invalid email `not-an-email`"),
+                (4, "duplicate email of row 2"),
+                (5, "name looks like a duplicate of row 2 (Liam Johnson)"),
+                (6, "name looks like a duplicate of row 2 (Liam Johnson)"),
+                (7, "missing name"),
+            ]
+        );
+    }
+
+    #[test]
+    fn similar_names_are_only_warnings_by_default() {
+        let (clean, rejects, warnings) = validate(
+            vec![
+                student(2, "Ada Obi", "ada@x.com"),
+                student(3, "Ada Obi", "ada2@x.com"),
+                student(4, "ada obi.", "ADA@x.com"),
+            ],
+            false,
+        );
+        let kept: Vec<u64> = clean.iter().map(|s| s.row).collect();
+        assert_eq!(kept, vec![2, 3]);
+        // Only the repeated email is rejected.
+        assert_eq!(rejects.len(), 1);
+        assert_eq!(rejects[0].row, 4);
+        assert_eq!(rejects[0].reason, "duplicate email of row 2");
+        assert_eq!(
+            warnings,
+            vec!["row 3: name looks like a duplicate of row 2 (Ada Obi)"]
+        );
+    }
+}
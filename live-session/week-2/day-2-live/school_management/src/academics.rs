@@ -0,0 +1,475 @@
+use crate::{SchoolError, StudentDetails, StudentsData};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Course {
+    pub code: String,
+    pub title: String,
+    /// Credit units, used to weight the course in the GPA.
+    pub credits: u32,
+}
+
+/// One graded piece of work, such as a quiz or the final exam.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Assessment {
+    pub title: String,
+    /// Relative weight within the course. Weights don't have to add up to 100;
+    /// the average is divided by their sum.
+    pub weight: f64,
+    pub score: f64,
+    pub max_score: f64,
+}
+
+/// Everything recorded for one student in one course.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CourseRecord {
+    /// Session number to whether the student was present.
+    pub attendance: BTreeMap<u32, bool>,
+    pub assessments: Vec<Assessment>,
+}
+
+impl CourseRecord {
+    /// Weighted average as a percentage, or `None` before anything is graded.
+    pub fn average(&self) -> Option<f64> {
+        let total_weight: f64 = self.assessments.iter().map(|a| a.weight).sum();
+        if total_weight == 0.0 {
+            return None;
+        }
+        let weighted: f64 = self
+            .assessments
+            .iter()
+            .map(|a| a.score / a.max_score * a.weight)
+            .sum();
+        Some(weighted / total_weight * 100.0)
+    }
+
+    /// Share of recorded sessions attended, from 0.0 to 1.0.
+    pub fn attendance_rate(&self) -> Option<f64> {
+        if self.attendance.is_empty() {
+            return None;
+        }
+        let present = self.attendance.values().filter(|&&p| p).count();
+        Some(present as f64 / self.attendance.len() as f64)
+    }
+}
+
+/// Letter grades on the five-point scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+}
+
+impl Grade {
+    pub fn from_percentage(percentage: f64) -> Grade {
+        match percentage {
+            p if p >= 70.0 => Grade::A,
+            p if p >= 60.0 => Grade::B,
+            p if p >= 50.0 => Grade::C,
+            p if p >= 45.0 => Grade::D,
+            p if p >= 40.0 => Grade::E,
+            _ => Grade::F,
+        }
+    }
+
+    pub fn points(self) -> f64 {
+        match self {
+            Grade::A => 5.0,
+            Grade::B => 4.0,
+            Grade::C => 3.0,
+            Grade::D => 2.0,
+            Grade::E => 1.0,
+            Grade::F => 0.0,
+        }
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Courses and each student's records in them. Being enrolled in a course
+/// means having a (possibly empty) record for it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Academics {
+    courses: BTreeMap<String, Course>,
+    /// Student id to course code to record.
+    enrolments: BTreeMap<u32, BTreeMap<String, CourseRecord>>,
+}
+
+impl Academics {
+    /// Checks that every enrolment points at a known student and course.
+    pub(crate) fn validate(&self, data: &StudentsData) -> Result<(), SchoolError> {
+        for (&id, courses) in &self.enrolments {
+            data.get_student(id)?;
+            for code in courses.keys() {
+                self.course(code)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn course(&self, code: &str) -> Result<&Course, SchoolError> {
+        self.courses
+            .get(code)
+            .ok_or_else(|| SchoolError::CourseNotFound(code.to_string()))
+    }
+}
+
+/// Course codes are stored upper-case without surrounding spaces.
+fn course_key(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// One line of a [`Transcript`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptLine {
+    pub course: Course,
+    pub average: Option<f64>,
+    pub grade: Option<Grade>,
+    pub attendance: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    pub student: StudentDetails,
+    pub lines: Vec<TranscriptLine>,
+    /// Credit-weighted grade points over graded courses.
+    pub gpa: Option<f64>,
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Transcript for {} (id {}, {})",
+            self.student.name, self.student.id, self.student.status
+        )?;
+        writeln!(
+            f,
+            "{:<10} {:<28} {:>7} {:>8} {:>5} {:>10}",
+            "Course", "Title", "Credits", "Average", "Grade", "Attendance"
+        )?;
+        let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        for line in &self.lines {
+            writeln!(
+                f,
+                "{:<10} {:<28} {:>7} {:>8} {:>5} {:>10}",
+                line.course.code,
+                line.course.title,
+                line.course.credits,
+                or_dash(line.average.map(|a| format!("{a:.1}"))),
+                or_dash(line.grade.map(|g| g.to_string())),
+                or_dash(line.attendance.map(|a| format!("{:.0}%", a * 100.0))),
+            )?;
+        }
+        write!(
+            f,
+            "GPA: {}",
+            or_dash(self.gpa.map(|gpa| format!("{gpa:.2} / 5.00")))
+        )
+    }
+}
+
+impl StudentsData {
+    pub fn add_course(
+        &mut self,
+        code: &str,
+        title: String,
+        credits: u32,
+    ) -> Result<(), SchoolError> {
+        let code = course_key(code);
+        if self.academics.courses.contains_key(&code) {
+            return Err(SchoolError::DuplicateCourse(code));
+        }
+        let course = Course {
+            code: code.clone(),
+            title,
+            credits,
+        };
+        self.academics.courses.insert(code, course);
+        Ok(())
+    }
+
+    pub fn courses(&self) -> impl Iterator<Item = &Course> {
+        self.academics.courses.values()
+    }
+
+    /// Enrols a student in a course. Enrolling twice is a no-op.
+    pub fn enrol(&mut self, id: u32, code: &str) -> Result<(), SchoolError> {
+        self.get_student(id)?;
+        let code = course_key(code);
+        self.academics.course(&code)?;
+        self.academics
+            .enrolments
+            .entry(id)
+            .or_default()
+            .entry(code)
+            .or_default();
+        Ok(())
+    }
+
+    /// Drops a student from a course along with its attendance and grades.
+    pub fn unenrol(&mut self, id: u32, code: &str) -> Result<CourseRecord, SchoolError> {
+        let code = course_key(code);
+        let courses = self.academics.enrolments.get_mut(&id);
+        courses
+            .and_then(|courses| courses.remove(&code))
+            .ok_or(SchoolError::NotEnrolled { id, course: code })
+    }
+
+    /// Students enrolled in a course, in id order.
+    pub fn enrolled_in(&self, code: &str) -> impl Iterator<Item = &StudentDetails> {
+        let code = course_key(code);
+        self.academics
+            .enrolments
+            .iter()
+            .filter(move |(_, courses)| courses.contains_key(&code))
+            .filter_map(|(id, _)| self.get_student(*id).ok())
+    }
+
+    /// Marks a student present or absent for a numbered session. Recording
+    /// the same session again overwrites it.
+    pub fn record_attendance(
+        &mut self,
+        id: u32,
+        code: &str,
+        session: u32,
+        present: bool,
+    ) -> Result<(), SchoolError> {
+        self.record_mut(id, code)?
+            .attendance
+            .insert(session, present);
+        Ok(())
+    }
+
+    pub fn record_assessment(
+        &mut self,
+        id: u32,
+        code: &str,
+        assessment: Assessment,
+    ) -> Result<(), SchoolError> {
+        let Assessment {
+            weight,
+            score,
+            max_score,
+            ..
+        } = assessment;
+        if !(max_score > 0.0 && weight > 0.0 && (0.0..=max_score).contains(&score)) {
+            return Err(SchoolError::InvalidAssessment(format!(
+                "score {score} of {max_score} with weight {weight}"
+            )));
+        }
+        self.record_mut(id, code)?.assessments.push(assessment);
+        Ok(())
+    }
+
+    pub fn course_record(&self, id: u32, code: &str) -> Result<&CourseRecord, SchoolError> {
+        self.get_student(id)?;
+        let code = course_key(code);
+        self.academics
+            .enrolments
+            .get(&id)
+            .and_then(|courses| courses.get(&code))
+            .ok_or(SchoolError::NotEnrolled { id, course: code })
+    }
+
+    fn record_mut(&mut self, id: u32, code: &str) -> Result<&mut CourseRecord, SchoolError> {
+        self.course_record(id, code)?;
+        let code = course_key(code);
+        Ok(self
+            .academics
+            .enrolments
+            .get_mut(&id)
+            .and_then(|courses| courses.get_mut(&code))
+            .expect("checked by course_record"))
+    }
+
+    /// Grade point average over the student's graded courses, weighted by credits.
+    pub fn gpa(&self, id: u32) -> Result<Option<f64>, SchoolError> {
+        Ok(self.transcript(id)?.gpa)
+    }
+
+    pub fn transcript(&self, id: u32) -> Result<Transcript, SchoolError> {
+        let student = self.get_student(id)?.clone();
+        let mut lines = Vec::new();
+        let (mut points, mut credits) = (0.0, 0);
+
+        for (code, record) in self.academics.enrolments.get(&id).into_iter().flatten() {
+            let course = self.academics.course(code)?.clone();
+            let average = record.average();
+            let grade = average.map(Grade::from_percentage);
+            if let Some(grade) = grade {
+                points += grade.points() * f64::from(course.credits);
+                credits += course.credits;
+            }
+            lines.push(TranscriptLine {
+                course,
+                average,
+                grade,
+                attendance: record.attendance_rate(),
+            });
+        }
+
+        let gpa = (credits > 0).then(|| points / f64::from(credits));
+        Ok(Transcript {
+            student,
+            lines,
+            gpa,
+        })
+    }
+
+    /// Forgets everything recorded for an evicted student.
+    pub(crate) fn drop_records(&mut self, id: u32) {
+        self.academics.enrolments.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assessment(title: &str, weight: f64, score: f64, max_score: f64) -> Assessment {
+        Assessment {
+            title: title.to_string(),
+            weight,
+            score,
+            max_score,
+        }
+    }
+
+    fn setup() -> StudentsData {
+        let mut data = StudentsData::new();
+        data.register("Joshua".to_string()).unwrap();
+        data.register("Armolas".to_string()).unwrap();
+        data.add_course("rs101", "Intro to Rust".to_string(), 3)
+            .unwrap();
+        data.add_course("SOR201", "Soroban Contracts".to_string(), 2)
+            .unwrap();
+        data.enrol(1, "RS101").unwrap();
+        data.enrol(1, "sor201").unwrap();
+        data
+    }
+
+    #[test]
+    fn test_enrolment_requires_known_student_and_course() {
+        let mut data = setup();
+        assert_eq!(data.enrol(9, "RS101"), Err(SchoolError::NotFound(9)));
+        assert_eq!(
+            data.enrol(1, "XX999"),
+            Err(SchoolError::CourseNotFound("XX999".to_string()))
+        );
+        assert_eq!(
+            data.record_attendance(2, "RS101", 1, true),
+            Err(SchoolError::NotEnrolled {
+                id: 2,
+                course: "RS101".to_string()
+            })
+        );
+        assert_eq!(
+            data.add_course(" rs101 ", "Again".to_string(), 1),
+            Err(SchoolError::DuplicateCourse("RS101".to_string()))
+        );
+
+        let enrolled: Vec<u32> = data.enrolled_in("rs101").map(|s| s.id).collect();
+        assert_eq!(enrolled, vec![1]);
+    }
+
+    #[test]
+    fn test_weighted_average_and_attendance() {
+        let mut data = setup();
+        data.record_assessment(1, "RS101", assessment("Quiz", 30.0, 8.0, 10.0))
+            .unwrap();
+        data.record_assessment(1, "RS101", assessment("Exam", 70.0, 60.0, 100.0))
+            .unwrap();
+        for (session, present) in [(1, true), (2, false), (3, true), (4, true)] {
+            data.record_attendance(1, "RS101", session, present)
+                .unwrap();
+        }
+        // Re-recording a session replaces it.
+        data.record_attendance(1, "RS101", 2, true).unwrap();
+
+        let record = data.course_record(1, "RS101").unwrap();
+        // 0.8 * 30 + 0.6 * 70 = 66
+        assert!((record.average().unwrap() - 66.0).abs() < 1e-9);
+        assert_eq!(record.attendance_rate(), Some(1.0));
+
+        assert!(matches!(
+            data.record_assessment(1, "RS101", assessment("Bad", 10.0, 11.0, 10.0)),
+            Err(SchoolError::InvalidAssessment(_))
+        ));
+    }
+
+    #[test]
+    fn test_gpa_weights_by_credits() {
+        let mut data = setup();
+        assert_eq!(data.gpa(1), Ok(None));
+
+        // RS101: 75% -> A (5 points) x 3 credits; SOR201: 55% -> C (3 points) x 2.
+        data.record_assessment(1, "RS101", assessment("Exam", 1.0, 75.0, 100.0))
+            .unwrap();
+        data.record_assessment(1, "SOR201", assessment("Exam", 1.0, 55.0, 100.0))
+            .unwrap();
+
+        let gpa = data.gpa(1).unwrap().unwrap();
+        assert!((gpa - 21.0 / 5.0).abs() < 1e-9);
+
+        let transcript = data.transcript(1).unwrap();
+        let grades: Vec<Option<Grade>> = transcript.lines.iter().map(|l| l.grade).collect();
+        assert_eq!(grades, vec![Some(Grade::A), Some(Grade::C)]);
+        assert!(transcript.to_string().ends_with("GPA: 4.20 / 5.00"));
+    }
+
+    #[test]
+    fn test_evict_drops_records() {
+        let mut data = setup();
+        data.record_attendance(1, "RS101", 1, true).unwrap();
+        data.evict_student(1).unwrap();
+        assert_eq!(data.enrolled_in("RS101").count(), 0);
+        assert!(data.academics.enrolments.is_empty());
+    }
+
+    #[test]
+    fn test_records_survive_save_and_load() {
+        let mut data = setup();
+        data.record_assessment(1, "RS101", assessment("Exam", 1.0, 75.0, 100.0))
+            .unwrap();
+        data.record_attendance(1, "SOR201", 1, false).unwrap();
+
+        let path = std::env::temp_dir().join(format!("academics_{}.json", std::process::id()));
+        data.save(&path).unwrap();
+        let loaded = StudentsData::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.academics, data.academics);
+        assert_eq!(loaded.transcript(1), data.transcript(1));
+    }
+
+    #[test]
+    fn test_grade_boundaries() {
+        let grades: Vec<Grade> = [100.0, 70.0, 69.9, 60.0, 50.0, 45.0, 40.0, 39.9]
+            .into_iter()
+            .map(Grade::from_percentage)
+            .collect();
+        assert_eq!(
+            grades,
+            vec![
+                Grade::A,
+                Grade::A,
+                Grade::B,
+                Grade::B,
+                Grade::C,
+                Grade::D,
+                Grade::E,
+                Grade::F
+            ]
+        );
+    }
+}
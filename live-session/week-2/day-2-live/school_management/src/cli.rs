@@ -0,0 +1,65 @@
+use clap::{Parser, Subcommand};
+use school_management::StudentStatus;
+use std::path::PathBuf;
+
+/// Keep track of students. Run without a command for the interactive menu.
+#[derive(Debug, Parser)]
+#[command(name = "school_management", version)]
+pub struct Args {
+    /// File the students are loaded from and saved to.
+    #[arg(long, default_value = "students.json", global = true)]
+    pub data: PathBuf,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Register a new student and print their id.
+    Register { name: String },
+    /// Remove a student and everything recorded for them.
+    Evict { id: u32 },
+    /// List students in id order.
+    List {
+        /// Only list students with this status.
+        #[arg(long)]
+        status: Option<StudentStatus>,
+    },
+    /// Change a student's name.
+    Rename { id: u32, name: String },
+    /// Move a student to a new status.
+    SetStatus { id: u32, status: StudentStatus },
+    /// Print a student's courses, grades and GPA.
+    Transcript { id: u32 },
+    /// Register every student in a roster CSV, skipping names already taken.
+    Import { path: PathBuf },
+    /// Write every student to a CSV file.
+    Export { path: PathBuf },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_args_are_consistent() {
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn test_list_status_filter() {
+        let args =
+            Args::try_parse_from(["school_management", "list", "--status", "active"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::List {
+                status: Some(StudentStatus::Active)
+            })
+        ));
+        assert!(
+            Args::try_parse_from(["school_management", "list", "--status", "expelled"]).is_err()
+        );
+    }
+}
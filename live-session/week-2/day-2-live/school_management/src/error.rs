@@ -0,0 +1,45 @@
+use crate::StudentStatus;
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchoolError {
+    /// No student has this id.
+    NotFound(u32),
+    /// Another student is already registered under this name.
+    DuplicateName(String),
+    /// The status change is not allowed, see [`StudentStatus::can_become`].
+    InvalidStatusTransition {
+        from: StudentStatus,
+        to: StudentStatus,
+    },
+    /// No course has this code.
+    CourseNotFound(String),
+    /// A course with this code already exists.
+    DuplicateCourse(String),
+    /// The student is not enrolled in the course.
+    NotEnrolled { id: u32, course: String },
+    /// A score outside `0..=max_score`, or a non-positive weight or maximum.
+    InvalidAssessment(String),
+}
+
+impl fmt::Display for SchoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchoolError::NotFound(id) => write!(f, "no student with id {id}"),
+            SchoolError::DuplicateName(name) => {
+                write!(f, "a student named `{name}` is already registered")
+            }
+            SchoolError::InvalidStatusTransition { from, to } => {
+                write!(f, "a student cannot go from {from} to {to}")
+            }
+            SchoolError::CourseNotFound(code) => write!(f, "no course with code {code}"),
+            SchoolError::DuplicateCourse(code) => write!(f, "course {code} already exists"),
+            SchoolError::NotEnrolled { id, course } => {
+                write!(f, "student {id} is not enrolled in {course}")
+            }
+            SchoolError::InvalidAssessment(detail) => write!(f, "invalid assessment: {detail}"),
+        }
+    }
+}
+
+impl Error for SchoolError {}
@@ -0,0 +1,468 @@
+mod academics;
+mod error;
+mod storage;
+
+use academics::Academics;
+pub use academics::{Assessment, Course, CourseRecord, Grade, Transcript, TranscriptLine};
+pub use error::SchoolError;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    str::FromStr,
+};
+pub use storage::{ImportReport, SkipReason, SkippedRow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StudentStatus {
+    Active,
+    Inactive,
+    Graduated,
+    Suspended,
+}
+
+impl StudentStatus {
+    /// The allowed status changes:
+    ///
+    /// - Active can become Inactive, Graduated or Suspended.
+    /// - Inactive can become Active again.
+    /// - Suspended can become Active or Inactive.
+    /// - Graduated is final.
+    pub fn can_become(self, next: StudentStatus) -> bool {
+        use StudentStatus::*;
+        matches!(
+            (self, next),
+            (Active, Inactive | Graduated | Suspended)
+                | (Inactive, Active)
+                | (Suspended, Active | Inactive)
+        )
+    }
+}
+
+impl fmt::Display for StudentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Parses a status name in any case, as typed on the command line.
+impl FromStr for StudentStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "active" => Ok(StudentStatus::Active),
+            "inactive" => Ok(StudentStatus::Inactive),
+            "graduated" => Ok(StudentStatus::Graduated),
+            "suspended" => Ok(StudentStatus::Suspended),
+            _ => Err(format!(
+                "unknown status `{s}` (expected active, inactive, graduated or suspended)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StudentDetails {
+    pub id: u32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub status: StudentStatus,
+}
+
+/// Every student keyed by id, plus secondary indexes by name and by status.
+///
+/// All mutations go through the methods below, which keep the indexes in
+/// step with `data`. Saved files only hold the students and `next_id`; the
+/// indexes are rebuilt on load.
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "storage::StoredData")]
+pub struct StudentsData {
+    data: HashMap<u32, StudentDetails>,
+    /// Normalised name (see [`name_key`]) to id.
+    by_name: HashMap<String, u32>,
+    /// Ids in each status, kept sorted so status queries list in id order.
+    by_status: HashMap<StudentStatus, BTreeSet<u32>>,
+    next_id: u32,
+    /// Courses, enrolments, attendance and grades.
+    academics: Academics,
+}
+
+impl Default for StudentsData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Names are compared ignoring case and surrounding spaces.
+fn name_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+impl StudentsData {
+    pub fn new() -> Self {
+        Self {
+            data: HashMap::new(),
+            by_name: HashMap::new(),
+            by_status: HashMap::new(),
+            next_id: 1,
+            academics: Academics::default(),
+        }
+    }
+
+    pub fn register(&mut self, name: String) -> Result<u32, SchoolError> {
+        self.check_name_free(&name, None)?;
+
+        let present_id = self.next_id;
+        let student = StudentDetails {
+            id: present_id,
+            name,
+            email: None,
+            status: StudentStatus::Active,
+        };
+        self.next_id += 1;
+        self.insert(student);
+        Ok(present_id)
+    }
+
+    /// Removes a student, along with their enrolments and records, and hands
+    /// back their details.
+    pub fn evict_student(&mut self, id: u32) -> Result<StudentDetails, SchoolError> {
+        let student = self.data.remove(&id).ok_or(SchoolError::NotFound(id))?;
+        self.drop_records(id);
+        self.by_name.remove(&name_key(&student.name));
+        if let Some(ids) = self.by_status.get_mut(&student.status) {
+            ids.remove(&id);
+        }
+        Ok(student)
+    }
+
+    pub fn get_student(&self, id: u32) -> Result<&StudentDetails, SchoolError> {
+        self.data.get(&id).ok_or(SchoolError::NotFound(id))
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&StudentDetails> {
+        self.by_name
+            .get(&name_key(name))
+            .and_then(|id| self.data.get(id))
+    }
+
+    pub fn update_student(&mut self, id: u32, new_name: String) -> Result<(), SchoolError> {
+        self.check_name_free(&new_name, Some(id))?;
+        let student = self.data.get_mut(&id).ok_or(SchoolError::NotFound(id))?;
+
+        self.by_name.remove(&name_key(&student.name));
+        self.by_name.insert(name_key(&new_name), id);
+        student.name = new_name;
+        Ok(())
+    }
+
+    /// Moves a student to a new status if [`StudentStatus::can_become`] allows it.
+    pub fn update_status(&mut self, id: u32, new_status: StudentStatus) -> Result<(), SchoolError> {
+        let student = self.data.get_mut(&id).ok_or(SchoolError::NotFound(id))?;
+        if !student.status.can_become(new_status) {
+            return Err(SchoolError::InvalidStatusTransition {
+                from: student.status,
+                to: new_status,
+            });
+        }
+
+        if let Some(ids) = self.by_status.get_mut(&student.status) {
+            ids.remove(&id);
+        }
+        self.by_status.entry(new_status).or_default().insert(id);
+        student.status = new_status;
+        Ok(())
+    }
+
+    /// `except` is the student being renamed, who may keep their own name.
+    fn check_name_free(&self, name: &str, except: Option<u32>) -> Result<(), SchoolError> {
+        match self.by_name.get(&name_key(name)) {
+            Some(&id) if Some(id) != except => Err(SchoolError::DuplicateName(name.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds a student to the store and both indexes.
+    fn insert(&mut self, student: StudentDetails) {
+        self.by_name.insert(name_key(&student.name), student.id);
+        self.by_status
+            .entry(student.status)
+            .or_default()
+            .insert(student.id);
+        self.data.insert(student.id, student);
+    }
+
+    /// Every student, in no particular order.
+    pub fn get_all_student(&self) -> impl Iterator<Item = &StudentDetails> {
+        self.data.values()
+    }
+
+    /// Every student in id order, for stable files and listings.
+    pub fn students_by_id(&self) -> impl Iterator<Item = &StudentDetails> {
+        let mut students: Vec<&StudentDetails> = self.data.values().collect();
+        students.sort_by_key(|student| student.id);
+        students.into_iter()
+    }
+
+    /// Students with the given status, in id order.
+    pub fn students_with_status(
+        &self,
+        status: StudentStatus,
+    ) -> impl Iterator<Item = &StudentDetails> {
+        self.by_status
+            .get(&status)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.data.get(id))
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Rebuilds both indexes from scratch and compares them with the live ones.
+    fn assert_indexes_consistent(data: &StudentsData) {
+        let mut expected = StudentsData::new();
+        for student in data.data.values() {
+            expected.insert(student.clone());
+        }
+        assert_eq!(data.by_name, expected.by_name);
+        let non_empty = |index: &HashMap<StudentStatus, BTreeSet<u32>>| {
+            index
+                .iter()
+                .filter(|(_, ids)| !ids.is_empty())
+                .map(|(status, ids)| (*status, ids.clone()))
+                .collect::<HashMap<_, _>>()
+        };
+        assert_eq!(non_empty(&data.by_status), non_empty(&expected.by_status));
+    }
+
+    fn setup() -> StudentsData {
+        let mut student_data = StudentsData::new();
+
+        student_data.register("Joshua".to_string()).unwrap();
+        student_data.register("Armolas".to_string()).unwrap();
+        student_data
+    }
+
+    #[test]
+    fn test_register_student() {
+        let data = setup();
+        assert_eq!(data.data.len(), 2);
+        assert_eq!(data.data[&1].name, "Joshua".to_string());
+        assert_eq!(data.data[&2].name, "Armolas".to_string());
+        assert_eq!(data.data[&2].id, 2);
+        assert_eq!(data.data[&2].status, StudentStatus::Active);
+    }
+
+    #[test]
+    fn test_get_student_by_id() {
+        let data = setup();
+        let student_details = data.get_student(1).unwrap();
+        assert_eq!(student_details.name, "Joshua".to_string());
+    }
+
+    #[test]
+    fn test_update_student_name() {
+        let mut data = setup();
+        let update_student = data.update_student(1, "Josh".to_string());
+        assert!(update_student.is_ok());
+        let new_name = data.get_student(1).unwrap();
+        assert_eq!(new_name.name, "Josh".to_string());
+    }
+
+    #[test]
+    fn test_evict_student() {
+        let mut data = setup();
+        let evicted = data.evict_student(1).unwrap();
+        assert_eq!(evicted.name, "Joshua".to_string());
+        assert_eq!(data.data.len(), 1);
+    }
+
+    #[test]
+    fn test_indexes_follow_every_change() {
+        let mut data = setup();
+        let tolu = data.register("Tolu".to_string()).unwrap();
+        data.update_student(1, "Josh".to_string()).unwrap();
+        data.update_status(2, StudentStatus::Suspended).unwrap();
+        data.update_status(tolu, StudentStatus::Suspended).unwrap();
+        data.evict_student(tolu).unwrap();
+        assert_indexes_consistent(&data);
+
+        assert!(data.find_by_name("Joshua").is_none());
+        assert_eq!(data.find_by_name(" JOSH ").unwrap().id, 1);
+        let suspended: Vec<u32> = data
+            .students_with_status(StudentStatus::Suspended)
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(suspended, vec![2]);
+        // The old name is free again once renamed away.
+        assert!(data.register("Joshua".to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_id_is_not_found() {
+        let mut data = setup();
+        assert_eq!(data.get_student(9).unwrap_err(), SchoolError::NotFound(9));
+        assert_eq!(data.evict_student(9).unwrap_err(), SchoolError::NotFound(9));
+        assert_eq!(
+            data.update_student(9, "Ghost".to_string()),
+            Err(SchoolError::NotFound(9))
+        );
+        assert_eq!(
+            data.update_status(9, StudentStatus::Inactive),
+            Err(SchoolError::NotFound(9))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_names_are_rejected() {
+        let mut data = setup();
+        assert_eq!(
+            data.register(" joshua ".to_string()),
+            Err(SchoolError::DuplicateName(" joshua ".to_string()))
+        );
+        assert_eq!(
+            data.update_student(2, "JOSHUA".to_string()),
+            Err(SchoolError::DuplicateName("JOSHUA".to_string()))
+        );
+        // Keeping your own name is not a clash.
+        assert!(data.update_student(1, "joshua".to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_get_all_students() {
+        let data = setup();
+        let students = data.get_all_student();
+        assert_eq!(students.count(), 2);
+    }
+
+    #[test]
+    fn test_save_and_load_keeps_next_id() {
+        let mut data = setup();
+        data.evict_student(2).unwrap();
+
+        let path = std::env::temp_dir().join(format!("school_{}.json", std::process::id()));
+        data.save(&path).unwrap();
+        let mut loaded = StudentsData::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.find_by_name("joshua").unwrap().id, 1);
+        assert_eq!(loaded.get_student(1).unwrap().name, "Joshua".to_string());
+        // Id 2 was used before the restart and must not be handed out again.
+        assert_eq!(loaded.register("Tolu".to_string()), Ok(3));
+    }
+
+    #[test]
+    fn test_import_and_export_csv() {
+        let mut data = StudentsData::new();
+        let roster = "Name,Email\nLiam Johnson,liam.johnson@example.com\n Emma Smith , \nliam johnson,other@example.com\n";
+        let report = data.import_csv_from(roster.as_bytes()).unwrap();
+        assert_eq!(report.added, 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].line, 4);
+        assert_eq!(report.skipped[0].reason, SkipReason::NameTaken(1));
+        assert_eq!(
+            data.get_student(1).unwrap().email,
+            Some("liam.johnson@example.com".to_string())
+        );
+        assert_eq!(data.get_student(2).unwrap().name, "Emma Smith".to_string());
+
+        let mut out = Vec::new();
+        data.export_csv_to(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Id,Name,Email,Status\n1,Liam Johnson,liam.johnson@example.com,Active\n2,Emma Smith,,Active\n"
+        );
+    }
+
+    #[test]
+    fn test_import_matches_on_email() {
+        let mut data = StudentsData::new();
+        let roster = "Name,Email\nAda Obi,ada@example.com\nTolu Ade,\n";
+        data.import_csv_from(roster.as_bytes()).unwrap();
+
+        // Re-importing with a renamed student and an extra row skips only the
+        // students already registered.
+        data.update_student(1, "Ada Obi-Eze".to_string()).unwrap();
+        let roster = "Name,Email\nAda Obi,ADA@example.com\nTolu Ade,\n,nobody@example.com\nEmma Smith,emma@example.com\n";
+        let report = data.import_csv_from(roster.as_bytes()).unwrap();
+
+        assert_eq!(report.added, 1);
+        let skipped: Vec<(u64, SkipReason)> = report
+            .skipped
+            .iter()
+            .map(|row| (row.line, row.reason.clone()))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                (2, SkipReason::AlreadyRegistered(1)),
+                (3, SkipReason::AlreadyRegistered(2)),
+                (4, SkipReason::MissingName),
+            ]
+        );
+        assert_eq!(
+            report.skipped[0].to_string(),
+            "line 2: Ada Obi is already registered as student 1"
+        );
+        assert_eq!(data.len(), 3);
+    }
+
+    #[test]
+    fn test_update_student_status() {
+        let mut data = setup();
+
+        let updated_status = data.update_status(2, StudentStatus::Inactive);
+        assert!(updated_status.is_ok());
+        assert_eq!(data.get_student(2).unwrap().status, StudentStatus::Inactive);
+    }
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!("active".parse(), Ok(StudentStatus::Active));
+        assert_eq!(" Graduated ".parse(), Ok(StudentStatus::Graduated));
+        assert!("expelled".parse::<StudentStatus>().is_err());
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        let mut data = setup();
+
+        data.update_status(1, StudentStatus::Suspended).unwrap();
+        assert_eq!(
+            data.update_status(1, StudentStatus::Graduated),
+            Err(SchoolError::InvalidStatusTransition {
+                from: StudentStatus::Suspended,
+                to: StudentStatus::Graduated,
+            })
+        );
+        data.update_status(1, StudentStatus::Active).unwrap();
+        data.update_status(1, StudentStatus::Graduated).unwrap();
+
+        // Graduation is final.
+        for next in [
+            StudentStatus::Active,
+            StudentStatus::Inactive,
+            StudentStatus::Suspended,
+        ] {
+            assert!(data.update_status(1, next).is_err());
+        }
+        assert_eq!(
+            data.get_student(1).unwrap().status,
+            StudentStatus::Graduated
+        );
+    }
+}
@@ -1,145 +1,69 @@
-#[derive(Debug, Clone, PartialEq)]
-enum StudentStatus {
-    Active,
-    Inactive,
-}
+mod cli;
+mod repl;
 
-#[derive(Debug, Clone)]
-struct StudentDetails {
-    id: u32,
-    name: String,
-    status: StudentStatus,
-}
+use clap::Parser;
+use cli::{Args, Command};
+use repl::Menu;
+use school_management::StudentsData;
+use std::{error::Error, io, process};
 
-struct StudentsData {
-    data: Vec<StudentDetails>,
-    next_id: u32,
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("error: {err}");
+        process::exit(1);
+    }
 }
 
-impl StudentsData {
-    fn new() -> Self {
-        Self {
-            data: Vec::new(),
-            next_id: 1,
+/// Loads the students, runs one subcommand (or the menu) and saves any change.
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let data_file = args.data.as_path();
+    let mut data = StudentsData::load(data_file)?;
+
+    let Some(command) = args.command else {
+        return Menu::new(io::stdin().lock(), io::stdout().lock())
+            .run(&mut data, |data| data.save(data_file));
+    };
+
+    match command {
+        Command::Register { name } => {
+            let id = data.register(name)?;
+            data.save(data_file)?;
+            println!("{id}");
         }
-    }
-
-    fn register(&mut self, name: String) -> u32 {
-        let present_id = self.next_id;
-        let student = StudentDetails {
-            id: present_id,
-            name,
-            status: StudentStatus::Active,
-        };
-        self.next_id += 1;
-        self.data.push(student);
-        present_id
-    }
-
-    fn evict_student2(&mut self, id: u32) -> bool {
-        if let Some(student_detail) = self.data.iter_mut().position(|student| student.id == id) {
-            self.data.remove(student_detail);
-            true
-        } else {
-            false
+        Command::Evict { id } => {
+            let student = data.evict_student(id)?;
+            data.save(data_file)?;
+            println!("Evicted {} ({})", student.name, student.id);
         }
-    }
-
-    fn evict_student(&mut self, id: u32) {
-        self.data.retain(|student_id| student_id.id != id);
-    }
-
-    fn get_student(&self, id: u32) -> &StudentDetails {
-        self.data
-            .iter()
-            .find(|student_id| student_id.id == id)
-            .unwrap()
-    }
-
-    fn update_student(&mut self, id: u32, new_name: String) -> bool {
-        if let Some(student) = self.data.iter_mut().find(|stu_id| stu_id.id == id) {
-            student.name = new_name;
-            true
-        } else {
-            false
+        Command::List { status } => {
+            let mut out = io::stdout().lock();
+            match status {
+                Some(status) => repl::write_students(&mut out, data.students_with_status(status))?,
+                None => repl::write_students(&mut out, data.students_by_id())?,
+            }
         }
-    }
-
-    fn update_status(&mut self, id: u32, new_status: StudentStatus) -> bool {
-        if let Some(student) = self.data.iter_mut().find(|student_id| student_id.id == id) {
-            student.status = new_status;
-            true
-        } else {
-            false
+        Command::Rename { id, name } => {
+            data.update_student(id, name)?;
+            data.save(data_file)?;
+        }
+        Command::SetStatus { id, status } => {
+            data.update_status(id, status)?;
+            data.save(data_file)?;
+        }
+        Command::Transcript { id } => println!("{}", data.transcript(id)?),
+        Command::Import { path } => {
+            let report = data.import_csv(&path)?;
+            data.save(data_file)?;
+            println!("Imported {} students from {}", report.added, path.display());
+            for row in &report.skipped {
+                eprintln!("skipped {row}");
+            }
+        }
+        Command::Export { path } => {
+            data.export_csv(&path)?;
+            println!("Exported {} students to {}", data.len(), path.display());
         }
     }
-
-    fn get_all_student(&self) -> &Vec<StudentDetails> {
-        &self.data
-    }
-}
-fn main() {
-    println!("Hello");
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    fn setup() -> StudentsData {
-        let mut student_data = StudentsData::new();
-
-        student_data.register("Joshua".to_string());
-        student_data.register("Armolas".to_string());
-        student_data
-    }
-
-    #[test]
-    fn test_register_student() {
-        let data = setup();
-        assert_eq!(data.data.len(), 2);
-        assert_eq!(data.data[0].name, "Joshua".to_string());
-        assert_eq!(data.data[1].name, "Armolas".to_string());
-        assert_eq!(data.data[1].id, 2);
-        assert_eq!(data.data[1].status, StudentStatus::Active);
-    }
-
-    #[test]
-    fn test_get_student_by_id() {
-        let data = setup();
-        let student_details = data.get_student(1);
-        assert_eq!(student_details.name, "Joshua".to_string());
-    }
-
-    #[test]
-    fn test_update_student_name() {
-        let mut data = setup();
-        let update_student = data.update_student(1, "Josh".to_string());
-        assert!(update_student);
-        let new_name = data.get_student(1);
-        assert_eq!(new_name.name, "Josh".to_string());
-    }
-
-    #[test]
-    fn test_evict_student() {
-        let mut data = setup();
-        data.evict_student(1);
-        assert_eq!(data.data.len(), 1);
-    }
-
-    #[test]
-    fn test_get_all_students() {
-        let data = setup();
-        let students = data.get_all_student();
-        assert_eq!(students.len(), 2);
-    }
-
-    #[test]
-    fn test_update_student_status() {
-        let mut data = setup();
-
-        let updated_status = data.update_status(2, StudentStatus::Inactive);
-        assert!(updated_status);
-        assert_eq!(data.get_student(2).status, StudentStatus::Inactive);
-    }
+    Ok(())
 }
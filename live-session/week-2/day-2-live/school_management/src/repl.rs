@@ -0,0 +1,282 @@
+use school_management::{StudentDetails, StudentStatus, StudentsData};
+use std::{
+    error::Error,
+    io::{self, BufRead, Write},
+};
+
+const MENU: &str = "\
+1) List students
+2) Register a student
+3) Rename a student
+4) Change a student's status
+5) Evict a student
+6) Show a transcript
+q) Quit";
+
+/// Writes one line per student, shared with the `list` subcommand.
+pub fn write_students<'a, W: Write>(
+    out: &mut W,
+    students: impl Iterator<Item = &'a StudentDetails>,
+) -> io::Result<()> {
+    let mut empty = true;
+    for student in students {
+        empty = false;
+        writeln!(
+            out,
+            "{:>4}  {:<24} {}",
+            student.id, student.name, student.status
+        )?;
+    }
+    if empty {
+        writeln!(out, "No students.")?;
+    }
+    Ok(())
+}
+
+/// The interactive menu. Every edit asks for confirmation, and an empty answer
+/// at any prompt cancels it. `save` runs after each confirmed edit.
+pub struct Menu<R, W> {
+    input: R,
+    out: W,
+}
+
+impl<R: BufRead, W: Write> Menu<R, W> {
+    pub fn new(input: R, out: W) -> Self {
+        Menu { input, out }
+    }
+
+    /// Runs until the user quits or input ends.
+    pub fn run(
+        &mut self,
+        data: &mut StudentsData,
+        mut save: impl FnMut(&StudentsData) -> Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        loop {
+            writeln!(self.out, "\n{MENU}")?;
+            let Some(choice) = self.prompt("> ")? else {
+                return Ok(());
+            };
+            let changed = match choice.as_str() {
+                "1" => {
+                    write_students(&mut self.out, data.students_by_id())?;
+                    false
+                }
+                "2" => self.register(data)?,
+                "3" => self.rename(data)?,
+                "4" => self.set_status(data)?,
+                "5" => self.evict(data)?,
+                "6" => {
+                    if let Some(id) = self.ask_id(data)? {
+                        writeln!(self.out, "{}", data.transcript(id)?)?;
+                    }
+                    false
+                }
+                "q" | "Q" | "0" => return Ok(()),
+                "" => false,
+                other => {
+                    writeln!(self.out, "Unknown choice `{other}`.")?;
+                    false
+                }
+            };
+            if changed {
+                save(data)?;
+            }
+        }
+    }
+
+    fn register(&mut self, data: &mut StudentsData) -> Result<bool, Box<dyn Error>> {
+        let Some(name) = self.ask("Name (empty to cancel): ")? else {
+            return self.cancelled();
+        };
+        if !self.confirm(&format!("Register {name}?"))? {
+            return self.cancelled();
+        }
+        match data.register(name) {
+            Ok(id) => {
+                writeln!(self.out, "Registered with id {id}.")?;
+                Ok(true)
+            }
+            Err(err) => self.failed(err),
+        }
+    }
+
+    fn rename(&mut self, data: &mut StudentsData) -> Result<bool, Box<dyn Error>> {
+        let Some(id) = self.ask_id(data)? else {
+            return self.cancelled();
+        };
+        let Some(name) = self.ask("New name (empty to cancel): ")? else {
+            return self.cancelled();
+        };
+        let old = data.get_student(id)?.name.clone();
+        if !self.confirm(&format!("Rename {old} to {name}?"))? {
+            return self.cancelled();
+        }
+        match data.update_student(id, name) {
+            Ok(()) => Ok(true),
+            Err(err) => self.failed(err),
+        }
+    }
+
+    fn set_status(&mut self, data: &mut StudentsData) -> Result<bool, Box<dyn Error>> {
+        let Some(id) = self.ask_id(data)? else {
+            return self.cancelled();
+        };
+        let status = loop {
+            let Some(answer) = self.ask("New status (empty to cancel): ")? else {
+                return self.cancelled();
+            };
+            match answer.parse::<StudentStatus>() {
+                Ok(status) => break status,
+                Err(err) => writeln!(self.out, "{err}")?,
+            }
+        };
+        let student = data.get_student(id)?;
+        let question = format!("Move {} from {} to {status}?", student.name, student.status);
+        if !self.confirm(&question)? {
+            return self.cancelled();
+        }
+        match data.update_status(id, status) {
+            Ok(()) => Ok(true),
+            Err(err) => self.failed(err),
+        }
+    }
+
+    fn evict(&mut self, data: &mut StudentsData) -> Result<bool, Box<dyn Error>> {
+        let Some(id) = self.ask_id(data)? else {
+            return self.cancelled();
+        };
+        let name = &data.get_student(id)?.name;
+        if !self.confirm(&format!("Evict {name} and all their records?"))? {
+            return self.cancelled();
+        }
+        let student = data.evict_student(id)?;
+        writeln!(self.out, "Evicted {}.", student.name)?;
+        Ok(true)
+    }
+
+    /// Asks until it gets the id of an existing student, or `None` to cancel.
+    fn ask_id(&mut self, data: &StudentsData) -> io::Result<Option<u32>> {
+        loop {
+            let Some(answer) = self.ask("Student id (empty to cancel): ")? else {
+                return Ok(None);
+            };
+            match answer.parse::<u32>() {
+                Ok(id) if data.get_student(id).is_ok() => return Ok(Some(id)),
+                Ok(id) => writeln!(self.out, "No student has id {id}.")?,
+                Err(_) => writeln!(self.out, "`{answer}` is not an id.")?,
+            }
+        }
+    }
+
+    /// Only an explicit yes goes ahead.
+    fn confirm(&mut self, question: &str) -> io::Result<bool> {
+        let answer = self.ask(&format!("{question} [y/N] "))?;
+        Ok(matches!(
+            answer.as_deref().map(str::to_lowercase).as_deref(),
+            Some("y" | "yes")
+        ))
+    }
+
+    /// Like [`Menu::prompt`], but an empty answer also counts as cancelling.
+    fn ask(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        Ok(self.prompt(prompt)?.filter(|answer| !answer.is_empty()))
+    }
+
+    /// Prints `prompt` and reads a trimmed line, or `None` at end of input.
+    fn prompt(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        write!(self.out, "{prompt}")?;
+        self.out.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim().to_string()))
+    }
+
+    fn cancelled(&mut self) -> Result<bool, Box<dyn Error>> {
+        writeln!(self.out, "Cancelled.")?;
+        Ok(false)
+    }
+
+    /// Reports a refused edit and carries on with the menu.
+    fn failed(&mut self, err: impl Error) -> Result<bool, Box<dyn Error>> {
+        writeln!(self.out, "Not changed: {err}.")?;
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Runs the menu over scripted input, returning what it printed and how
+    /// many times it saved.
+    fn run_script(data: &mut StudentsData, script: &str) -> (String, usize) {
+        let mut out = Vec::new();
+        let mut saves = 0;
+        Menu::new(script.as_bytes(), &mut out)
+            .run(data, |_| {
+                saves += 1;
+                Ok(())
+            })
+            .unwrap();
+        (String::from_utf8(out).unwrap(), saves)
+    }
+
+    fn setup() -> StudentsData {
+        let mut data = StudentsData::new();
+        data.register("Joshua".to_string()).unwrap();
+        data.register("Armolas".to_string()).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_confirmed_edits_are_applied_and_saved() {
+        let mut data = setup();
+        let (_, saves) = run_script(
+            &mut data,
+            "2\nTolu\ny\n3\n1\nJosh\nyes\n4\n2\ngraduated\ny\nq\n",
+        );
+        assert_eq!(saves, 3);
+        assert_eq!(data.find_by_name("tolu").unwrap().id, 3);
+        assert_eq!(data.get_student(1).unwrap().name, "Josh");
+        assert_eq!(
+            data.get_student(2).unwrap().status,
+            StudentStatus::Graduated
+        );
+    }
+
+    #[test]
+    fn test_declined_and_empty_answers_cancel() {
+        let mut data = setup();
+        // Decline the eviction, then cancel a rename at the name prompt.
+        let (out, saves) = run_script(&mut data, "5\n1\nn\n3\n2\n\n");
+        assert_eq!(saves, 0);
+        assert_eq!(out.matches("Cancelled.").count(), 2);
+        assert_eq!(data.len(), 2);
+        assert_eq!(data.get_student(2).unwrap().name, "Armolas");
+    }
+
+    #[test]
+    fn test_bad_input_is_asked_again() {
+        let mut data = setup();
+        let (out, saves) = run_script(&mut data, "5\nx\n9\n1\ny\n4\n2\nexpelled\nsuspended\ny\n");
+        assert!(out.contains("`x` is not an id."));
+        assert!(out.contains("No student has id 9."));
+        assert!(out.contains("unknown status `expelled`"));
+        assert_eq!(saves, 2);
+        assert!(data.get_student(1).is_err());
+        assert_eq!(
+            data.get_student(2).unwrap().status,
+            StudentStatus::Suspended
+        );
+    }
+
+    #[test]
+    fn test_refused_edit_is_reported() {
+        let mut data = setup();
+        let (out, saves) = run_script(&mut data, "3\n2\njoshua\ny\n");
+        assert_eq!(saves, 0);
+        assert!(out.contains("Not changed: a student named `joshua` is already registered."));
+    }
+}
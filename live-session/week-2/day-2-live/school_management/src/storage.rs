@@ -0,0 +1,232 @@
+use crate::{SchoolError, StudentDetails, StudentsData, academics::Academics};
+use csv::{ReaderBuilder, Trim, WriterBuilder};
+use serde::{Deserialize, Serialize, Serializer};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// A row of a roster such as csv_grouping's `students.csv`.
+#[derive(Debug, Deserialize)]
+struct RosterRow {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Email", default)]
+    email: String,
+}
+
+/// A roster row that `import_csv` did not register.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedRow {
+    /// Line in the CSV file, counting the header as line 1.
+    pub line: u64,
+    pub name: String,
+    pub email: String,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    MissingName,
+    /// This student, found by email (or by name for rows without one), is
+    /// already registered.
+    AlreadyRegistered(u32),
+    /// A different student already has this name; rename one of them and
+    /// import the row again.
+    NameTaken(u32),
+}
+
+impl fmt::Display for SkippedRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match self.reason {
+            SkipReason::MissingName => write!(f, "no name given"),
+            SkipReason::AlreadyRegistered(id) => {
+                write!(f, "{} is already registered as student {id}", self.name)
+            }
+            SkipReason::NameTaken(id) => write!(
+                f,
+                "{} <{}> has the same name as student {id}",
+                self.name, self.email
+            ),
+        }
+    }
+}
+
+/// What `import_csv` did with a roster.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub added: usize,
+    pub skipped: Vec<SkippedRow>,
+}
+
+/// On-disk shape of [`StudentsData`]: the students in id order, the next id
+/// and the academic records. `S` and `A` are borrowed when saving so nothing
+/// has to be cloned.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct StoredData<S = StudentDetails, A = Academics> {
+    students: Vec<S>,
+    next_id: u32,
+    /// Missing from files saved before courses existed.
+    #[serde(default)]
+    academics: A,
+}
+
+impl TryFrom<StoredData> for StudentsData {
+    type Error = String;
+
+    fn try_from(stored: StoredData) -> Result<Self, Self::Error> {
+        let mut data = StudentsData::new();
+        for student in stored.students {
+            if student.id >= stored.next_id {
+                return Err(format!(
+                    "student id {} is not below next_id {}",
+                    student.id, stored.next_id
+                ));
+            }
+            if data.data.contains_key(&student.id) {
+                return Err(format!("student id {} appears twice", student.id));
+            }
+            data.check_name_free(&student.name, None)
+                .map_err(|err| err.to_string())?;
+            data.insert(student);
+        }
+        data.next_id = stored.next_id;
+        stored
+            .academics
+            .validate(&data)
+            .map_err(|err| err.to_string())?;
+        data.academics = stored.academics;
+        Ok(data)
+    }
+}
+
+impl Serialize for StudentsData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StoredData {
+            students: self.students_by_id().collect(),
+            next_id: self.next_id,
+            academics: &self.academics,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl StudentsData {
+    /// Loads saved students, or starts empty when the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(StudentsData::new()),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    /// Saves every student and the next id, so ids are never reused after a
+    /// restart. Writes to a temporary file first so a crash can't leave a
+    /// half-written save behind.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let tmp = path.with_extension("json.tmp");
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        drop(writer);
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Registers every row of a `Name,Email` roster and reports which rows
+    /// were skipped and why.
+    ///
+    /// Students are matched on email, ignoring case, so re-importing the same
+    /// roster is harmless. Rows without an email are matched on name. Since
+    /// names are unique, a new email under a name that is already taken is
+    /// skipped as [`SkipReason::NameTaken`] rather than registered.
+    pub fn import_csv(&mut self, path: &Path) -> Result<ImportReport, Box<dyn Error>> {
+        self.import_csv_from(File::open(path)?)
+    }
+
+    pub fn import_csv_from<R: Read>(&mut self, reader: R) -> Result<ImportReport, Box<dyn Error>> {
+        let mut reader = ReaderBuilder::new()
+            .has_headers(true)
+            .trim(Trim::All)
+            .from_reader(reader);
+        let headers = reader.headers()?.clone();
+
+        let mut by_email: HashMap<String, u32> = self
+            .data
+            .values()
+            .filter_map(|student| Some((student.email.as_ref()?.to_lowercase(), student.id)))
+            .collect();
+        let mut report = ImportReport::default();
+        for record in reader.records() {
+            let record = record?;
+            let line = record.position().map_or(0, |position| position.line());
+            let row: RosterRow = record.deserialize(Some(&headers))?;
+            let email = row.email.to_lowercase();
+            let skip = |reason| SkippedRow {
+                line,
+                name: row.name.clone(),
+                email: row.email.clone(),
+                reason,
+            };
+
+            if row.name.is_empty() {
+                report.skipped.push(skip(SkipReason::MissingName));
+                continue;
+            }
+            if let Some(&id) = by_email.get(&email).filter(|_| !email.is_empty()) {
+                report.skipped.push(skip(SkipReason::AlreadyRegistered(id)));
+                continue;
+            }
+
+            let id = match self.register(row.name.clone()) {
+                Ok(id) => id,
+                Err(SchoolError::DuplicateName(_)) => {
+                    let existing = self.find_by_name(&row.name).map_or(0, |student| student.id);
+                    report.skipped.push(skip(match email.is_empty() {
+                        true => SkipReason::AlreadyRegistered(existing),
+                        false => SkipReason::NameTaken(existing),
+                    }));
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if let Some(student) = self.data.get_mut(&id) {
+                student.email = Some(row.email).filter(|email| !email.is_empty());
+            }
+            if !email.is_empty() {
+                by_email.insert(email, id);
+            }
+            report.added += 1;
+        }
+        Ok(report)
+    }
+
+    /// Writes `Id,Name,Email,Status`. The `Name` and `Email` columns mean the
+    /// export can be fed straight back into csv_grouping.
+    pub fn export_csv(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        self.export_csv_to(File::create(path)?)
+    }
+
+    pub fn export_csv_to<W: Write>(&self, writer: W) -> Result<(), Box<dyn Error>> {
+        let mut writer = WriterBuilder::new().has_headers(true).from_writer(writer);
+        writer.write_record(["Id", "Name", "Email", "Status"])?;
+        for student in self.students_by_id() {
+            writer.write_record([
+                student.id.to_string().as_str(),
+                &student.name,
+                student.email.as_deref().unwrap_or(""),
+                &student.status.to_string(),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+//! The garage task from week 2 on top of the library: a few employees swipe
+//! their cards at each zone, and every attempt lands in an audit log.
+//!
+//! ```text
+//! cargo run --example garage [policy.toml] [audit.jsonl]
+//! ```
+
+use chrono::Local;
+use library_structure::{
+    AccessController, AuditLog, EmployeeStatus, EmployeeType, Employees, Policy, Zone,
+};
+use std::{env, error::Error, process};
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let policy = match args.next() {
+        Some(path) => Policy::load(path.as_ref())?,
+        None => Policy::default(),
+    };
+    let log = AuditLog::new(args.next().unwrap_or_else(|| "audit.jsonl".to_string()));
+    let mut office = AccessController::new(policy).with_audit_log(log);
+
+    let staff = [
+        ("EMP-001", EmployeeType::MediaTeam, EmployeeStatus::Active),
+        (
+            "EMP-002",
+            EmployeeType::ITDepartment,
+            EmployeeStatus::Active,
+        ),
+        ("EMP-003", EmployeeType::Manager, EmployeeStatus::Terminated),
+        (
+            "EMP-004",
+            EmployeeType::KitchenStaff,
+            EmployeeStatus::Active,
+        ),
+        (
+            "EMP-005",
+            EmployeeType::SocialMedia,
+            EmployeeStatus::OnLeave,
+        ),
+    ];
+    for (n, (id, role, status)) in staff.into_iter().enumerate() {
+        office.add_employee(Employees::new(id, role, status))?;
+        office.issue_keycard(id, &format!("K-{}", 100 + n))?;
+    }
+
+    let now = Local::now().naive_local();
+    for n in 0..staff.len() {
+        let card = format!("K-{}", 100 + n);
+        for zone in [Zone::Garage, Zone::ServerRoom, Zone::Kitchen] {
+            match office.swipe(&card, zone, now) {
+                Ok(employee) => println!("{card} {zone:?}: {:?} let in", employee.role),
+                Err(err) => println!("{card} {zone:?}: {err}"),
+            }
+        }
+    }
+
+    let log = office.audit_log().expect("set above");
+    println!("Logged to {}", log.path().display());
+    Ok(())
+}
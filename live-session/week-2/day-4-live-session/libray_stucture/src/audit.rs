@@ -0,0 +1,213 @@
+use crate::{
+    policy::Zone,
+    usage::{AccessDenied, DenialReason},
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    Granted,
+    Denied,
+}
+
+/// One keycard swipe, as written to the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Staff id, or `None` when the card presented belongs to nobody.
+    pub employee: Option<String>,
+    /// The card swiped, when the attempt was made with one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keycard: Option<String>,
+    pub zone: Zone,
+    pub timestamp: NaiveDateTime,
+    pub decision: Decision,
+    /// Set when the attempt was denied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<DenialReason>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        employee: Option<&str>,
+        keycard: Option<&str>,
+        zone: Zone,
+        timestamp: NaiveDateTime,
+        outcome: &Result<(), AccessDenied>,
+    ) -> Self {
+        let (decision, reason) = match outcome {
+            Ok(()) => (Decision::Granted, None),
+            Err(denied) => (Decision::Denied, Some(denied.reason.clone())),
+        };
+        AuditEntry {
+            employee: employee.map(str::to_string),
+            keycard: keycard.map(str::to_string),
+            zone,
+            timestamp,
+            decision,
+            reason,
+        }
+    }
+}
+
+/// An append-only log of access attempts, one JSON object per line.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// The file is created on the first append.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        AuditLog { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &AuditEntry) -> io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        // One write per entry, so concurrent appenders don't interleave lines.
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+
+    /// Every entry in the order it was written. A missing log is empty.
+    pub fn entries(&self) -> io::Result<Vec<AuditEntry>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Denied attempts made on any day from `from` to `to`, both included.
+    pub fn denied_between(&self, from: NaiveDate, to: NaiveDate) -> io::Result<Vec<AuditEntry>> {
+        let mut entries = self.entries()?;
+        entries.retain(|entry| {
+            entry.decision == Decision::Denied && (from..=to).contains(&entry.timestamp.date())
+        });
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        employee::{EmployeeStatus, EmployeeType, Employees},
+        policy::Policy,
+        usage::{AttemptError, attempt_access},
+    };
+    use std::fs;
+
+    fn temp_log(name: &str) -> AuditLog {
+        let path = std::env::temp_dir().join(format!("{name}_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        AuditLog::new(path)
+    }
+
+    fn at(text: &str) -> NaiveDateTime {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn every_attempt_is_logged() {
+        let log = temp_log("audit_every_attempt");
+        let policy = Policy::default();
+        let manager = Employees::new("EMP-001", EmployeeType::Manager, EmployeeStatus::Active);
+        let cook = Employees::new(
+            "EMP-002",
+            EmployeeType::KitchenStaff,
+            EmployeeStatus::Active,
+        );
+
+        attempt_access(
+            &policy,
+            &log,
+            &manager,
+            Zone::Garage,
+            at("2024-06-03T09:00:00"),
+        )
+        .unwrap();
+        let err = attempt_access(
+            &policy,
+            &log,
+            &cook,
+            Zone::Garage,
+            at("2024-06-03T09:05:00"),
+        );
+        assert!(matches!(err, Err(AttemptError::Denied(_))));
+
+        let entries = log.entries().unwrap();
+        fs::remove_file(log.path()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].decision, Decision::Granted);
+        assert_eq!(entries[0].reason, None);
+        assert_eq!(entries[1].employee.as_deref(), Some("EMP-002"));
+        assert_eq!(
+            entries[1].reason,
+            Some(DenialReason::NotAllowedForRole {
+                role: EmployeeType::KitchenStaff,
+                zone: Zone::Garage
+            })
+        );
+    }
+
+    #[test]
+    fn denied_attempts_by_date_range() {
+        let log = temp_log("audit_denied_between");
+        let policy = Policy::default();
+        let cook = Employees::new(
+            "EMP-002",
+            EmployeeType::KitchenStaff,
+            EmployeeStatus::Active,
+        );
+        let manager = Employees::new("EMP-001", EmployeeType::Manager, EmployeeStatus::Active);
+
+        for day in ["2024-06-01", "2024-06-02", "2024-06-03", "2024-06-04"] {
+            let time = at(&format!("{day}T12:00:00"));
+            let _ = attempt_access(&policy, &log, &cook, Zone::Garage, time);
+            attempt_access(&policy, &log, &manager, Zone::Garage, time).unwrap();
+        }
+
+        let date = |text: &str| text.parse::<NaiveDate>().unwrap();
+        let denied = log
+            .denied_between(date("2024-06-02"), date("2024-06-03"))
+            .unwrap();
+        fs::remove_file(log.path()).unwrap();
+
+        let days: Vec<NaiveDate> = denied.iter().map(|e| e.timestamp.date()).collect();
+        assert_eq!(days, vec![date("2024-06-02"), date("2024-06-03")]);
+        assert!(
+            denied
+                .iter()
+                .all(|e| e.employee.as_deref() == Some("EMP-002"))
+        );
+    }
+
+    #[test]
+    fn missing_log_is_empty() {
+        let log = temp_log("audit_missing");
+        assert!(log.entries().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    employee::{EmployeeStatus, Employees},
+    policy::{Policy, Zone},
+    usage::{AccessDenied, AttemptError, DenialReason},
+};
+use chrono::NaiveDateTime;
+use std::{collections::HashMap, error::Error, fmt};
+
+/// Owns the staff list, the policy and (optionally) the audit log, and keeps
+/// a keycard index so a swipe can be traced back to its employee.
+///
+/// Employees are only changed through the controller, so the index always
+/// matches their cards.
+#[derive(Debug, Default)]
+pub struct AccessController {
+    policy: Policy,
+    employees: HashMap<String, Employees>,
+    /// Every card ever issued, current or revoked, to the employee it was
+    /// issued to.
+    keycards: HashMap<String, String>,
+    log: Option<AuditLog>,
+}
+
+impl AccessController {
+    pub fn new(policy: Policy) -> Self {
+        AccessController {
+            policy,
+            ..AccessController::default()
+        }
+    }
+
+    /// Records every swipe from now on in `log`.
+    pub fn with_audit_log(mut self, log: AuditLog) -> Self {
+        self.log = Some(log);
+        self
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: Policy) {
+        self.policy = policy;
+    }
+
+    pub fn audit_log(&self) -> Option<&AuditLog> {
+        self.log.as_ref()
+    }
+
+    /// Takes on a new employee. Any cards they carry are indexed.
+    pub fn add_employee(&mut self, employee: Employees) -> Result<(), ControllerError> {
+        if self.employees.contains_key(&employee.id) {
+            return Err(ControllerError::DuplicateEmployee(employee.id));
+        }
+        let cards = employee.keycard.iter().chain(&employee.revoked_keycards);
+        for card in cards.clone() {
+            self.check_card_free(card)?;
+        }
+        for card in cards {
+            self.keycards.insert(card.clone(), employee.id.clone());
+        }
+        self.employees.insert(employee.id.clone(), employee);
+        Ok(())
+    }
+
+    pub fn employee(&self, id: &str) -> Result<&Employees, ControllerError> {
+        self.employees
+            .get(id)
+            .ok_or_else(|| ControllerError::UnknownEmployee(id.to_string()))
+    }
+
+    pub fn employees(&self) -> impl Iterator<Item = &Employees> {
+        self.employees.values()
+    }
+
+    /// The employee a card was issued to, whether or not it still works.
+    pub fn holder_of(&self, keycard: &str) -> Option<&Employees> {
+        self.keycards
+            .get(keycard)
+            .and_then(|id| self.employees.get(id))
+    }
+
+    pub fn set_status(&mut self, id: &str, status: EmployeeStatus) -> Result<(), ControllerError> {
+        self.employee_mut(id)?.status = status;
+        Ok(())
+    }
+
+    /// Gives the employee a new card, revoking and returning their old one.
+    /// Card ids are never reused, so a card issued before is refused.
+    pub fn issue_keycard(
+        &mut self,
+        id: &str,
+        keycard: &str,
+    ) -> Result<Option<String>, ControllerError> {
+        self.employee(id)?;
+        self.check_card_free(keycard)?;
+        self.keycards.insert(keycard.to_string(), id.to_string());
+        Ok(self.employee_mut(id)?.issue_keycard(keycard))
+    }
+
+    pub fn revoke_keycard(&mut self, id: &str) -> Result<Option<String>, ControllerError> {
+        Ok(self.employee_mut(id)?.revoke_keycard())
+    }
+
+    /// Decides whether employee `id` may enter `zone` at `at`, without logging.
+    pub fn check_access(
+        &self,
+        id: &str,
+        zone: Zone,
+        at: NaiveDateTime,
+    ) -> Result<Result<(), AccessDenied>, ControllerError> {
+        Ok(self.policy.check_access_at(self.employee(id)?, zone, at))
+    }
+
+    /// Handles a card swipe at `zone`, logging the outcome if there is a log.
+    /// Returns the employee who was let in.
+    pub fn swipe(
+        &self,
+        keycard: &str,
+        zone: Zone,
+        at: NaiveDateTime,
+    ) -> Result<&Employees, AttemptError> {
+        let holder = self.holder_of(keycard);
+        let decision = match holder {
+            Some(employee) => self.policy.check_keycard_at(employee, keycard, zone, at),
+            None => Err(AccessDenied {
+                reason: DenialReason::UnknownKeycard {
+                    keycard: keycard.to_string(),
+                },
+            }),
+        };
+
+        if let Some(log) = &self.log {
+            let id = holder.map(|employee| employee.id.as_str());
+            log.append(&AuditEntry::new(id, Some(keycard), zone, at, &decision))
+                .map_err(AttemptError::Audit)?;
+        }
+        decision?;
+        Ok(holder.expect("granted swipes have a holder"))
+    }
+
+    fn employee_mut(&mut self, id: &str) -> Result<&mut Employees, ControllerError> {
+        self.employees
+            .get_mut(id)
+            .ok_or_else(|| ControllerError::UnknownEmployee(id.to_string()))
+    }
+
+    fn check_card_free(&self, keycard: &str) -> Result<(), ControllerError> {
+        match self.keycards.get(keycard) {
+            Some(holder) => Err(ControllerError::KeycardTaken {
+                keycard: keycard.to_string(),
+                holder: holder.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControllerError {
+    /// No employee has this id.
+    UnknownEmployee(String),
+    /// An employee with this id is already on the books.
+    DuplicateEmployee(String),
+    /// The card id has already been issued, possibly to someone else.
+    KeycardTaken { keycard: String, holder: String },
+}
+
+impl fmt::Display for ControllerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControllerError::UnknownEmployee(id) => write!(f, "no employee with id {id}"),
+            ControllerError::DuplicateEmployee(id) => write!(f, "employee {id} already exists"),
+            ControllerError::KeycardTaken { keycard, holder } => {
+                write!(f, "keycard {keycard} was already issued to {holder}")
+            }
+        }
+    }
+}
+
+impl Error for ControllerError {}
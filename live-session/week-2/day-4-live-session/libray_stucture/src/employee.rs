@@ -0,0 +1,92 @@
+use crate::usage::{AccessDenied, DenialReason};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmployeeType {
+    MediaTeam,
+    #[serde(rename = "it_department")]
+    ITDepartment,
+    Manager,
+    SocialMedia,
+    TechnicalSupervisor,
+    KitchenStaff,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmployeeStatus {
+    Active,
+    Terminated,
+    /// Barred until the given local time, after which access comes back
+    /// on its own.
+    Suspended {
+        until: NaiveDateTime,
+    },
+    OnLeave,
+}
+
+impl EmployeeStatus {
+    /// The status that applies at `at`: a suspension that has run out counts
+    /// as [`EmployeeStatus::Active`].
+    pub fn at(self, at: NaiveDateTime) -> EmployeeStatus {
+        match self {
+            EmployeeStatus::Suspended { until } if at >= until => EmployeeStatus::Active,
+            status => status,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Employees {
+    /// Staff id, used to look up per-employee overrides in the [`Policy`](crate::Policy).
+    pub id: String,
+    pub role: EmployeeType,
+    pub status: EmployeeStatus,
+    /// The card that currently opens doors, if one has been issued.
+    pub keycard: Option<String>,
+    /// Cards that were lost, replaced or taken back. They never work again.
+    pub revoked_keycards: BTreeSet<String>,
+}
+
+impl Employees {
+    /// A new employee with no keycard yet.
+    pub fn new(id: impl Into<String>, role: EmployeeType, status: EmployeeStatus) -> Self {
+        Employees {
+            id: id.into(),
+            role,
+            status,
+            keycard: None,
+            revoked_keycards: BTreeSet::new(),
+        }
+    }
+
+    /// Hands out a new card, revoking the current one, which is returned.
+    pub fn issue_keycard(&mut self, keycard: impl Into<String>) -> Option<String> {
+        let old = self.revoke_keycard();
+        self.keycard = Some(keycard.into());
+        old
+    }
+
+    /// Takes the current card back, e.g. when it is reported lost.
+    pub fn revoke_keycard(&mut self) -> Option<String> {
+        let old = self.keycard.take()?;
+        self.revoked_keycards.insert(old.clone());
+        Some(old)
+    }
+
+    /// Checks that `keycard` is this employee's current card and has never
+    /// been revoked, even if the same id was later handed out again.
+    pub fn verify_keycard(&self, keycard: &str) -> Result<(), AccessDenied> {
+        let keycard = keycard.to_string();
+        let reason = if self.revoked_keycards.contains(&keycard) {
+            DenialReason::RevokedKeycard { keycard }
+        } else if self.keycard.as_ref() != Some(&keycard) {
+            DenialReason::UnknownKeycard { keycard }
+        } else {
+            return Ok(());
+        };
+        Err(AccessDenied { reason })
+    }
+}
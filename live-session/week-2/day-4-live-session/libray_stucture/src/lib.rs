@@ -1,2 +1,33 @@
-pub mod mode;
-pub mod usage;
+//! Keycard access control for the office: which employees may enter which
+//! zones, and when.
+//!
+//! An [`AccessController`] owns the staff list and a [`Policy`] mapping roles
+//! to zones, and answers card swipes, optionally writing each one to an
+//! [`AuditLog`].
+//!
+//! ```
+//! use chrono::NaiveDateTime;
+//! use library_structure::{AccessController, EmployeeStatus, EmployeeType, Employees, Policy, Zone};
+//!
+//! let mut office = AccessController::new(Policy::default());
+//! office
+//!     .add_employee(Employees::new("EMP-001", EmployeeType::Manager, EmployeeStatus::Active))
+//!     .unwrap();
+//! office.issue_keycard("EMP-001", "K-100").unwrap();
+//!
+//! let at: NaiveDateTime = "2024-06-03T09:00:00".parse().unwrap();
+//! assert!(office.swipe("K-100", Zone::Garage, at).is_ok());
+//! assert!(office.swipe("K-999", Zone::Garage, at).is_err());
+//! ```
+
+mod audit;
+mod controller;
+mod employee;
+mod policy;
+mod usage;
+
+pub use audit::{AuditEntry, AuditLog, Decision};
+pub use controller::{AccessController, ControllerError};
+pub use employee::{EmployeeStatus, EmployeeType, Employees};
+pub use policy::{Override, Policy, PolicyError, Window, Zone};
+pub use usage::{AccessDenied, AttemptError, DenialReason, attempt_access, print_access};
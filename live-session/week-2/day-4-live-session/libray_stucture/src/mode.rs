@@ -1,5 +0,0 @@
-use crate::usage::*;
-
-fn do_something(role: EmployeeType, status: EmployeeStatus) {
-    let employee = Employees { role, status };
-}
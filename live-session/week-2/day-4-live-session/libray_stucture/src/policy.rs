@@ -0,0 +1,283 @@
+use crate::employee::EmployeeType;
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    fmt, fs, io,
+    path::Path,
+};
+
+/// The places a keycard can open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Zone {
+    Garage,
+    ServerRoom,
+    Kitchen,
+}
+
+/// Extra zones opened or closed for one employee, on top of their role.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Override {
+    pub allow: BTreeSet<Zone>,
+    /// Wins over both the role and `allow`.
+    pub deny: BTreeSet<Zone>,
+}
+
+/// A stretch of the day, on some days of the week, when a role may badge in.
+///
+/// `start` is inclusive and `end` exclusive. A window whose end is not after
+/// its start runs past midnight, e.g. 22:00 to 06:00 for a night shift.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Window {
+    /// Empty means every day.
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl Window {
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        if !self.days.is_empty() && !self.days.contains(&at.weekday()) {
+            return false;
+        }
+        let time = at.time();
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Which zones each role may enter, with per-employee overrides keyed by
+/// employee id. A role missing from `roles` may enter nowhere. A role with
+/// entries in `windows` may only badge in during one of them; other roles
+/// may come in at any time.
+///
+/// In TOML:
+///
+/// ```toml
+/// [roles]
+/// manager = ["garage", "server_room", "kitchen"]
+/// kitchen_staff = ["kitchen"]
+///
+/// [[windows.kitchen_staff]]
+/// days = ["Mon", "Tue", "Wed", "Thu", "Fri"]
+/// start = "07:00"
+/// end = "18:00"
+///
+/// [overrides.EMP-007]
+/// allow = ["server_room"]
+/// deny = ["garage"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub roles: HashMap<EmployeeType, BTreeSet<Zone>>,
+    #[serde(default)]
+    pub windows: HashMap<EmployeeType, Vec<Window>>,
+    #[serde(default)]
+    pub overrides: HashMap<String, Override>,
+}
+
+/// The rules from the garage task: media, IT and managers may use the garage.
+impl Default for Policy {
+    fn default() -> Self {
+        let garage = BTreeSet::from([Zone::Garage]);
+        Policy {
+            roles: HashMap::from([
+                (EmployeeType::MediaTeam, garage.clone()),
+                (EmployeeType::ITDepartment, garage.clone()),
+                (EmployeeType::Manager, garage),
+            ]),
+            windows: HashMap::new(),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl Policy {
+    pub fn from_toml(text: &str) -> Result<Self, PolicyError> {
+        toml::from_str(text).map_err(PolicyError::Toml)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, PolicyError> {
+        serde_json::from_str(text).map_err(PolicyError::Json)
+    }
+
+    /// Reads a `.toml` or `.json` policy file, going by the extension.
+    pub fn load(path: &Path) -> Result<Self, PolicyError> {
+        let parse = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Policy::from_toml,
+            Some("json") => Policy::from_json,
+            _ => return Err(PolicyError::UnknownFormat(path.display().to_string())),
+        };
+        parse(&fs::read_to_string(path).map_err(PolicyError::Io)?)
+    }
+
+    /// Whether the role itself opens the zone, ignoring overrides.
+    pub fn role_allows(&self, role: EmployeeType, zone: Zone) -> bool {
+        self.roles
+            .get(&role)
+            .is_some_and(|zones| zones.contains(&zone))
+    }
+
+    /// Whether `at` falls inside one of the role's windows, if it has any.
+    pub fn within_hours(&self, role: EmployeeType, at: NaiveDateTime) -> bool {
+        match self.windows.get(&role) {
+            Some(windows) if !windows.is_empty() => windows.iter().any(|w| w.contains(at)),
+            _ => true,
+        }
+    }
+
+    pub fn override_for(&self, employee_id: &str) -> Option<&Override> {
+        self.overrides.get(employee_id)
+    }
+}
+
+#[derive(Debug)]
+pub enum PolicyError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    /// The file is neither `.toml` nor `.json`.
+    UnknownFormat(String),
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::Io(err) => write!(f, "could not read policy: {err}"),
+            PolicyError::Toml(err) => write!(f, "invalid TOML policy: {err}"),
+            PolicyError::Json(err) => write!(f, "invalid JSON policy: {err}"),
+            PolicyError::UnknownFormat(path) => {
+                write!(f, "policy {path} should end in .toml or .json")
+            }
+        }
+    }
+}
+
+impl Error for PolicyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PolicyError::Io(err) => Some(err),
+            PolicyError::Toml(err) => Some(err),
+            PolicyError::Json(err) => Some(err),
+            PolicyError::UnknownFormat(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_and_json_give_the_same_policy() {
+        let from_toml = Policy::from_toml(
+            r#"
+            [roles]
+            manager = ["garage", "server_room"]
+            kitchen_staff = ["kitchen"]
+
+            [overrides.EMP-007]
+            allow = ["server_room"]
+            "#,
+        )
+        .unwrap();
+        let from_json = Policy::from_json(
+            r#"{
+                "roles": {
+                    "manager": ["garage", "server_room"],
+                    "kitchen_staff": ["kitchen"]
+                },
+                "overrides": { "EMP-007": { "allow": ["server_room"] } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(from_toml, from_json);
+        assert!(from_toml.role_allows(EmployeeType::Manager, Zone::ServerRoom));
+        assert!(!from_toml.role_allows(EmployeeType::MediaTeam, Zone::Garage));
+        assert!(from_toml.override_for("EMP-007").unwrap().deny.is_empty());
+    }
+
+    #[test]
+    fn windows_limit_the_hours() {
+        let policy = Policy::from_toml(
+            r#"
+            [[windows.kitchen_staff]]
+            days = ["Mon", "Tue", "Wed", "Thu", "Fri"]
+            start = "07:00"
+            end = "18:00"
+
+            [[windows.technical_supervisor]]
+            start = "22:00"
+            end = "06:00"
+            "#,
+        )
+        .unwrap();
+        let at = |text: &str| text.parse::<NaiveDateTime>().unwrap();
+
+        // 2024-06-03 is a Monday and 2024-06-08 a Saturday.
+        let kitchen = EmployeeType::KitchenStaff;
+        assert!(policy.within_hours(kitchen, at("2024-06-03T07:00:00")));
+        assert!(policy.within_hours(kitchen, at("2024-06-03T17:59:59")));
+        assert!(!policy.within_hours(kitchen, at("2024-06-03T18:00:00")));
+        assert!(!policy.within_hours(kitchen, at("2024-06-03T06:59:00")));
+        assert!(!policy.within_hours(kitchen, at("2024-06-08T12:00:00")));
+
+        let night = EmployeeType::TechnicalSupervisor;
+        assert!(policy.within_hours(night, at("2024-06-08T23:30:00")));
+        assert!(policy.within_hours(night, at("2024-06-09T05:00:00")));
+        assert!(!policy.within_hours(night, at("2024-06-09T12:00:00")));
+
+        assert!(policy.within_hours(EmployeeType::Manager, at("2024-06-08T03:00:00")));
+    }
+
+    #[test]
+    fn unknown_zones_are_rejected() {
+        let err = Policy::from_toml("[roles]\nmanager = [\"roof\"]").unwrap_err();
+        assert!(matches!(err, PolicyError::Toml(_)));
+    }
+
+    #[test]
+    fn sample_policy_file_loads() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("policy.toml");
+        let policy = Policy::load(&path).unwrap();
+        assert!(policy.role_allows(EmployeeType::KitchenStaff, Zone::Kitchen));
+        assert!(matches!(
+            Policy::load(&path.with_file_name("missing.toml")),
+            Err(PolicyError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn unsupported_extension_is_rejected_before_reading() {
+        let path = std::env::temp_dir().join(format!("policy_{}.yaml", std::process::id()));
+        fs::write(&path, "roles: {}\n").unwrap();
+        let result = Policy::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(PolicyError::UnknownFormat(_))));
+        // A missing file with the wrong extension is still a format error.
+        assert!(matches!(
+            Policy::load(&path),
+            Err(PolicyError::UnknownFormat(_))
+        ));
+    }
+
+    #[test]
+    fn default_matches_the_garage_task() {
+        let policy = Policy::default();
+        assert!(policy.role_allows(EmployeeType::ITDepartment, Zone::Garage));
+        assert!(!policy.role_allows(EmployeeType::KitchenStaff, Zone::Garage));
+        assert!(!policy.role_allows(EmployeeType::Manager, Zone::Kitchen));
+    }
+}
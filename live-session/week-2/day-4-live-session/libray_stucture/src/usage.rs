@@ -1,43 +1,175 @@
-#[derive(Debug)]
-pub enum EmployeeType {
-    MediaTeam,
-    ITDepartment,
-    Manager,
-    SocialMedia,
-    TechnicalSupervisor,
-    KitchenStaff,
-}
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    employee::{EmployeeStatus, EmployeeType, Employees},
+    policy::{Policy, Zone},
+};
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt, io};
 
-#[derive(Debug)]
-pub enum EmployeeStatus {
-    Active,
+/// Why the keycard was refused.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DenialReason {
+    /// Terminated employees are refused everywhere.
     Terminated,
+    /// Suspended until the given time.
+    Suspended {
+        until: NaiveDateTime,
+    },
+    OnLeave,
+    /// The card presented has been revoked.
+    RevokedKeycard {
+        keycard: String,
+    },
+    /// The card presented is not the employee's current card.
+    UnknownKeycard {
+        keycard: String,
+    },
+    /// The policy has an explicit deny for this employee and zone.
+    DeniedForEmployee {
+        zone: Zone,
+    },
+    /// Neither the role nor an override opens the zone.
+    NotAllowedForRole {
+        role: EmployeeType,
+        zone: Zone,
+    },
+    /// The zone is allowed, but not at this time or on this day.
+    OutsideHours {
+        role: EmployeeType,
+        at: NaiveDateTime,
+    },
 }
-#[derive(Debug)]
-pub struct Employees {
-    pub role: EmployeeType,
-    pub status: EmployeeStatus,
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessDenied {
+    pub reason: DenialReason,
+}
+
+impl fmt::Display for AccessDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            DenialReason::Terminated => write!(f, "access denied: employee is terminated"),
+            DenialReason::Suspended { until } => {
+                write!(f, "access denied: employee is suspended until {until}")
+            }
+            DenialReason::OnLeave => write!(f, "access denied: employee is on leave"),
+            DenialReason::RevokedKeycard { keycard } => {
+                write!(f, "access denied: keycard {keycard} has been revoked")
+            }
+            DenialReason::UnknownKeycard { keycard } => {
+                write!(
+                    f,
+                    "access denied: keycard {keycard} is not issued to this employee"
+                )
+            }
+            DenialReason::DeniedForEmployee { zone } => {
+                write!(f, "access denied: employee is barred from the {zone:?}")
+            }
+            DenialReason::NotAllowedForRole { role, zone } => {
+                write!(f, "access denied: {role:?} may not enter the {zone:?}")
+            }
+            DenialReason::OutsideHours { role, at } => {
+                write!(f, "access denied: {role:?} may not enter at {at}")
+            }
+        }
+    }
+}
+
+impl Error for AccessDenied {}
+
+impl Policy {
+    /// Decides whether `employee` may enter `zone` right now, by the local clock.
+    pub fn check_access(&self, employee: &Employees, zone: Zone) -> Result<(), AccessDenied> {
+        self.check_access_at(employee, zone, Local::now().naive_local())
+    }
+
+    /// Decides whether `employee` may enter `zone` at the given local time.
+    ///
+    /// The employee's status at `at` is checked first, then their own deny
+    /// list, then their own allow list and their role, and finally the role's
+    /// time windows.
+    pub fn check_access_at(
+        &self,
+        employee: &Employees,
+        zone: Zone,
+        at: NaiveDateTime,
+    ) -> Result<(), AccessDenied> {
+        let deny = |reason| Err(AccessDenied { reason });
+
+        match employee.status.at(at) {
+            EmployeeStatus::Active => {}
+            EmployeeStatus::Terminated => return deny(DenialReason::Terminated),
+            EmployeeStatus::Suspended { until } => return deny(DenialReason::Suspended { until }),
+            EmployeeStatus::OnLeave => return deny(DenialReason::OnLeave),
+        }
+
+        let rules = self.override_for(&employee.id);
+        if rules.is_some_and(|rules| rules.deny.contains(&zone)) {
+            return deny(DenialReason::DeniedForEmployee { zone });
+        }
+        let allowed = rules.is_some_and(|rules| rules.allow.contains(&zone))
+            || self.role_allows(employee.role, zone);
+        if !allowed {
+            return deny(DenialReason::NotAllowedForRole {
+                role: employee.role,
+                zone,
+            });
+        }
+
+        if !self.within_hours(employee.role, at) {
+            return deny(DenialReason::OutsideHours {
+                role: employee.role,
+                at,
+            });
+        }
+        Ok(())
+    }
+
+    /// Like [`Policy::check_access_at`], for someone swiping `keycard`. The
+    /// card must be the employee's current one.
+    pub fn check_keycard_at(
+        &self,
+        employee: &Employees,
+        keycard: &str,
+        zone: Zone,
+        at: NaiveDateTime,
+    ) -> Result<(), AccessDenied> {
+        employee.verify_keycard(keycard)?;
+        self.check_access_at(employee, zone, at)
+    }
 }
 
-pub fn check_access(ept: &Employees) -> Result<(), String> {
-    // You can choose the version you want to use or just learn both,
-    // tried this when i got home so as to give youa shorter and more
-    // relatable syntax
+/// An access attempt failed either because it was refused or because it
+/// could not be written to the audit log. Unlogged attempts are never let in.
+#[derive(Debug)]
+pub enum AttemptError {
+    Denied(AccessDenied),
+    Audit(io::Error),
+}
 
-    if let EmployeeStatus::Terminated = ept.status {
-        return Err("You are not allowed!!".to_string());
+impl fmt::Display for AttemptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttemptError::Denied(denied) => denied.fmt(f),
+            AttemptError::Audit(err) => write!(f, "could not write audit log: {err}"),
+        }
     }
+}
 
-    //match ept.status {
-    //    EmployeeStatus::Terminated => return Err("You are not allowed!!".to_string()),
-    //    _ => (),
-    //};
+impl Error for AttemptError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AttemptError::Denied(denied) => Some(denied),
+            AttemptError::Audit(err) => Some(err),
+        }
+    }
+}
 
-    match ept.role {
-        EmployeeType::MediaTeam => Ok(()),
-        EmployeeType::ITDepartment => Ok(()),
-        EmployeeType::Manager => Ok(()),
-        _ => Err("Access denied".to_string()),
+impl From<AccessDenied> for AttemptError {
+    fn from(denied: AccessDenied) -> Self {
+        AttemptError::Denied(denied)
     }
 }
 
@@ -45,22 +177,232 @@ pub fn print_access(print_access: &Employees) {
     println!("The {:?} is {:?}", print_access.role, print_access.status);
 }
 
-pub fn attempt_access(employee: &Employees) -> Result<(), String> {
-    check_access(employee)?;
+/// Checks the keycard at `at`, appends the outcome to `log` and prints the
+/// employee when they get in.
+pub fn attempt_access(
+    policy: &Policy,
+    log: &AuditLog,
+    employee: &Employees,
+    zone: Zone,
+    at: NaiveDateTime,
+) -> Result<(), AttemptError> {
+    let decision = policy.check_access_at(employee, zone, at);
+    log.append(&AuditEntry::new(
+        Some(&employee.id),
+        None,
+        zone,
+        at,
+        &decision,
+    ))
+    .map_err(AttemptError::Audit)?;
+    decision?;
     print_access(employee);
     Ok(())
 }
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn policy() -> Policy {
+        Policy::from_toml(
+            r#"
+            [roles]
+            it_department = ["garage", "server_room"]
+            kitchen_staff = ["kitchen"]
+
+            [overrides.EMP-002]
+            allow = ["garage"]
+
+            [overrides.EMP-003]
+            deny = ["server_room"]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn roles_open_their_zones() {
+        let policy = policy();
+        let it = Employees::new(
+            "EMP-001",
+            EmployeeType::ITDepartment,
+            EmployeeStatus::Active,
+        );
+        assert_eq!(policy.check_access(&it, Zone::ServerRoom), Ok(()));
+        assert_eq!(
+            policy.check_access(&it, Zone::Kitchen),
+            Err(AccessDenied {
+                reason: DenialReason::NotAllowedForRole {
+                    role: EmployeeType::ITDepartment,
+                    zone: Zone::Kitchen
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn overrides_beat_the_role() {
+        let policy = policy();
+        let cook = Employees::new(
+            "EMP-002",
+            EmployeeType::KitchenStaff,
+            EmployeeStatus::Active,
+        );
+        assert_eq!(policy.check_access(&cook, Zone::Garage), Ok(()));
+
+        let it = Employees::new(
+            "EMP-003",
+            EmployeeType::ITDepartment,
+            EmployeeStatus::Active,
+        );
+        assert_eq!(
+            policy
+                .check_access(&it, Zone::ServerRoom)
+                .unwrap_err()
+                .reason,
+            DenialReason::DeniedForEmployee {
+                zone: Zone::ServerRoom
+            }
+        );
+    }
+
+    #[test]
+    fn terminated_employees_are_always_denied() {
+        let policy = policy();
+        let cook = Employees::new(
+            "EMP-002",
+            EmployeeType::KitchenStaff,
+            EmployeeStatus::Terminated,
+        );
+        for zone in [Zone::Garage, Zone::Kitchen] {
+            assert_eq!(
+                policy.check_access(&cook, zone).unwrap_err().reason,
+                DenialReason::Terminated
+            );
+        }
+    }
+
     #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    fn suspensions_run_out_on_their_own() {
+        let policy = policy();
+        let at = |text: &str| text.parse::<NaiveDateTime>().unwrap();
+        let until = at("2024-06-10T00:00:00");
+        let mut it = Employees::new(
+            "EMP-001",
+            EmployeeType::ITDepartment,
+            EmployeeStatus::Suspended { until },
+        );
+
+        let during = at("2024-06-09T23:59:59");
+        assert_eq!(
+            policy
+                .check_access_at(&it, Zone::Garage, during)
+                .unwrap_err()
+                .reason,
+            DenialReason::Suspended { until }
+        );
+        assert_eq!(policy.check_access_at(&it, Zone::Garage, until), Ok(()));
+
+        it.status = EmployeeStatus::OnLeave;
+        assert_eq!(
+            policy
+                .check_access_at(&it, Zone::Garage, until)
+                .unwrap_err()
+                .reason,
+            DenialReason::OnLeave
+        );
+    }
+
+    #[test]
+    fn only_the_current_keycard_opens_doors() {
+        let policy = policy();
+        let at = "2024-06-03T09:00:00".parse::<NaiveDateTime>().unwrap();
+        let mut it = Employees::new(
+            "EMP-001",
+            EmployeeType::ITDepartment,
+            EmployeeStatus::Active,
+        );
+        assert_eq!(
+            policy
+                .check_keycard_at(&it, "K-1", Zone::Garage, at)
+                .unwrap_err()
+                .reason,
+            DenialReason::UnknownKeycard {
+                keycard: "K-1".to_string()
+            }
+        );
+
+        assert_eq!(it.issue_keycard("K-1"), None);
+        assert_eq!(
+            policy.check_keycard_at(&it, "K-1", Zone::Garage, at),
+            Ok(())
+        );
+
+        // Reissuing revokes the lost card for good.
+        assert_eq!(it.issue_keycard("K-2"), Some("K-1".to_string()));
+        assert_eq!(
+            policy
+                .check_keycard_at(&it, "K-1", Zone::Garage, at)
+                .unwrap_err()
+                .reason,
+            DenialReason::RevokedKeycard {
+                keycard: "K-1".to_string()
+            }
+        );
+        assert_eq!(
+            policy.check_keycard_at(&it, "K-2", Zone::Garage, at),
+            Ok(())
+        );
+
+        assert_eq!(it.revoke_keycard(), Some("K-2".to_string()));
+        assert!(it.keycard.is_none());
+        assert_eq!(it.revoked_keycards.len(), 2);
+    }
+
+    #[test]
+    fn kitchen_staff_only_during_opening_hours() {
+        let mut policy = policy();
+        policy.windows = Policy::from_toml(
+            r#"
+            [[windows.kitchen_staff]]
+            start = "07:00"
+            end = "18:00"
+            "#,
+        )
+        .unwrap()
+        .windows;
+        let cook = Employees::new(
+            "EMP-009",
+            EmployeeType::KitchenStaff,
+            EmployeeStatus::Active,
+        );
+        let at = |text: &str| text.parse::<NaiveDateTime>().unwrap();
+
+        let morning = at("2024-06-03T07:30:00");
+        assert_eq!(
+            policy.check_access_at(&cook, Zone::Kitchen, morning),
+            Ok(())
+        );
+
+        let night = at("2024-06-03T21:00:00");
+        assert_eq!(
+            policy
+                .check_access_at(&cook, Zone::Kitchen, night)
+                .unwrap_err()
+                .reason,
+            DenialReason::OutsideHours {
+                role: EmployeeType::KitchenStaff,
+                at: night
+            }
+        );
+        // A zone the role can't enter at all is reported as such, not as the hour.
+        assert!(matches!(
+            policy
+                .check_access_at(&cook, Zone::ServerRoom, night)
+                .unwrap_err()
+                .reason,
+            DenialReason::NotAllowedForRole { .. }
+        ));
     }
 }
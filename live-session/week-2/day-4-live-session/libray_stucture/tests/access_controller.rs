@@ -0,0 +1,167 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use library_structure::{
+    AccessController, AttemptError, AuditLog, ControllerError, Decision, DenialReason,
+    EmployeeStatus, EmployeeType, Employees, Policy, Zone,
+};
+use std::{fs, path::PathBuf};
+
+fn at(text: &str) -> NaiveDateTime {
+    text.parse().unwrap()
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}_{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+/// The sample policy shipped with the crate, plus three employees with cards.
+fn office() -> AccessController {
+    let policy =
+        Policy::load(&PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("policy.toml")).unwrap();
+    let mut office = AccessController::new(policy);
+    for (id, role, card) in [
+        ("EMP-001", EmployeeType::ITDepartment, "K-1"),
+        ("EMP-002", EmployeeType::KitchenStaff, "K-2"),
+        ("EMP-003", EmployeeType::SocialMedia, "K-3"),
+    ] {
+        office
+            .add_employee(Employees::new(id, role, EmployeeStatus::Active))
+            .unwrap();
+        office.issue_keycard(id, card).unwrap();
+    }
+    office
+}
+
+fn denial(result: Result<&Employees, AttemptError>) -> DenialReason {
+    match result {
+        Err(AttemptError::Denied(denied)) => denied.reason,
+        other => panic!("expected a denial, got {other:?}"),
+    }
+}
+
+#[test]
+fn swipes_follow_the_policy_file() {
+    let office = office();
+    let monday_noon = at("2024-06-03T12:00:00");
+
+    let it = office.swipe("K-1", Zone::ServerRoom, monday_noon).unwrap();
+    assert_eq!(it.id, "EMP-001");
+    assert!(office.swipe("K-2", Zone::Kitchen, monday_noon).is_ok());
+    assert_eq!(
+        denial(office.swipe("K-3", Zone::Garage, monday_noon)),
+        DenialReason::NotAllowedForRole {
+            role: EmployeeType::SocialMedia,
+            zone: Zone::Garage
+        }
+    );
+    assert!(matches!(
+        denial(office.swipe("K-2", Zone::Kitchen, at("2024-06-03T19:00:00"))),
+        DenialReason::OutsideHours { .. }
+    ));
+    assert_eq!(
+        denial(office.swipe("K-404", Zone::Kitchen, monday_noon)),
+        DenialReason::UnknownKeycard {
+            keycard: "K-404".to_string()
+        }
+    );
+}
+
+#[test]
+fn lifecycle_changes_take_effect_without_rebuilding() {
+    let mut office = office();
+    let noon = at("2024-06-03T12:00:00");
+
+    office
+        .set_status(
+            "EMP-001",
+            EmployeeStatus::Suspended {
+                until: at("2024-06-05T00:00:00"),
+            },
+        )
+        .unwrap();
+    assert!(matches!(
+        denial(office.swipe("K-1", Zone::Garage, noon)),
+        DenialReason::Suspended { .. }
+    ));
+    assert!(
+        office
+            .swipe("K-1", Zone::Garage, at("2024-06-05T08:00:00"))
+            .is_ok()
+    );
+
+    assert_eq!(
+        office.issue_keycard("EMP-001", "K-10"),
+        Ok(Some("K-1".to_string()))
+    );
+    assert_eq!(
+        denial(office.swipe("K-1", Zone::Garage, noon + chrono::Duration::days(3))),
+        DenialReason::RevokedKeycard {
+            keycard: "K-1".to_string()
+        }
+    );
+    assert_eq!(
+        office.issue_keycard("EMP-003", "K-1"),
+        Err(ControllerError::KeycardTaken {
+            keycard: "K-1".to_string(),
+            holder: "EMP-001".to_string()
+        })
+    );
+
+    office
+        .set_status("EMP-001", EmployeeStatus::Terminated)
+        .unwrap();
+    assert_eq!(
+        office.check_access("EMP-001", Zone::Garage, noon),
+        Ok(Err(library_structure::AccessDenied {
+            reason: DenialReason::Terminated
+        }))
+    );
+}
+
+#[test]
+fn swipes_are_audited() {
+    let path = temp_path("controller_audit");
+    let office = office().with_audit_log(AuditLog::new(&path));
+
+    let _ = office.swipe("K-3", Zone::Garage, at("2024-06-01T10:00:00"));
+    office
+        .swipe("K-1", Zone::Garage, at("2024-06-02T10:00:00"))
+        .unwrap();
+    let _ = office.swipe("K-404", Zone::Garage, at("2024-06-03T10:00:00"));
+
+    let log = office.audit_log().unwrap();
+    let entries = log.entries().unwrap();
+    let denied = log
+        .denied_between(
+            NaiveDate::from_ymd_opt(2024, 6, 2).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 6, 30).unwrap(),
+        )
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let decisions: Vec<Decision> = entries.iter().map(|e| e.decision).collect();
+    assert_eq!(
+        decisions,
+        vec![Decision::Denied, Decision::Granted, Decision::Denied]
+    );
+    assert_eq!(denied.len(), 1);
+    assert_eq!(denied[0].employee, None);
+    assert_eq!(denied[0].keycard.as_deref(), Some("K-404"));
+}
+
+#[test]
+fn employees_are_unique() {
+    let mut office = office();
+    let again = Employees::new("EMP-002", EmployeeType::Manager, EmployeeStatus::Active);
+    assert_eq!(
+        office.add_employee(again),
+        Err(ControllerError::DuplicateEmployee("EMP-002".to_string()))
+    );
+    assert_eq!(
+        office.set_status("EMP-404", EmployeeStatus::OnLeave),
+        Err(ControllerError::UnknownEmployee("EMP-404".to_string()))
+    );
+    assert_eq!(office.holder_of("K-2").unwrap().id, "EMP-002");
+    assert_eq!(office.employees().count(), 3);
+}
@@ -0,0 +1,549 @@
+mod output;
+mod search;
+mod walk;
+
+use output::Printer;
+use std::{
+    env,
+    error::Error,
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, IsTerminal, Write},
+    path::Path,
+    sync::mpsc::{self, Receiver, SyncSender},
+};
+use walk::FileFilter;
+
+pub use search::{
+    ContextSearcher, Event, Line, Matcher, search, search_case_insensitive, search_lines,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Highlight only when stdout is a terminal.
+    Auto,
+    Always,
+    Never,
+}
+
+pub struct Config {
+    pub query: String,
+    pub paths: Vec<String>,
+    pub ignore_case: bool,
+    pub regex: bool,
+    pub line_numbers: bool,
+    pub count: bool,
+    pub recursive: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Lines of context to print before each match.
+    pub before: usize,
+    /// Lines of context to print after each match.
+    pub after: usize,
+    pub color: ColorChoice,
+    pub json: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    MissingQuery,
+    MissingValue(String),
+    InvalidValue(String, String),
+    UnknownFlag(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::MissingQuery => write!(f, "missing search query"),
+            ConfigError::MissingValue(flag) => write!(f, "`{flag}` needs a value"),
+            ConfigError::InvalidValue(flag, value) => {
+                write!(f, "invalid value `{value}` for `{flag}`")
+            }
+            ConfigError::UnknownFlag(flag) => write!(f, "unknown flag `{flag}`"),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+pub const USAGE: &str = "usage: minigrep [-i] [-E] [-n] [-c] [-r] [-A N] [-B N] [-C N] [--json] [--color WHEN] [--include GLOB] [--exclude GLOB] <query> [<path>...]";
+
+/// Parses `minigrep [flags] <query> [<path>...]`.
+///
+/// With no path, or a path of `-`, standard input is searched.
+/// Case-insensitive search is turned on by `-i` or by setting the
+/// `IGNORE_CASE` environment variable. Everything after `--` is treated as
+/// the query and paths, even if it starts with `-`. `-C` sets both context
+/// sizes; a later `-A` or `-B` overrides one side.
+pub fn parse_config(args: &[String]) -> Result<Config, ConfigError> {
+    let mut config = Config {
+        query: String::new(),
+        paths: Vec::new(),
+        ignore_case: env::var_os("IGNORE_CASE").is_some(),
+        regex: false,
+        line_numbers: false,
+        count: false,
+        recursive: false,
+        include: Vec::new(),
+        exclude: Vec::new(),
+        before: 0,
+        after: 0,
+        color: ColorChoice::Auto,
+        json: false,
+    };
+    let mut positional = Vec::new();
+
+    // Skip the program name.
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            // Short context flags may carry their number: `-A3`.
+            _ if arg.len() > 2 && ["-A", "-B", "-C"].iter().any(|f| arg.starts_with(f)) => {
+                (&arg[..2], Some(arg[2..].to_string()))
+            }
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| ConfigError::MissingValue(flag.to_string()))
+        };
+        let number = |value: String| {
+            value
+                .parse::<usize>()
+                .map_err(|_| ConfigError::InvalidValue(flag.to_string(), value))
+        };
+
+        match flag {
+            "-i" | "--ignore-case" => config.ignore_case = true,
+            "-E" | "--regex" => config.regex = true,
+            "-n" | "--line-number" => config.line_numbers = true,
+            "-c" | "--count" => config.count = true,
+            "-r" | "--recursive" => config.recursive = true,
+            "--include" => config.include.push(value()?),
+            "--exclude" => config.exclude.push(value()?),
+            "-A" | "--after-context" => config.after = number(value()?)?,
+            "-B" | "--before-context" => config.before = number(value()?)?,
+            "-C" | "--context" => {
+                let lines = number(value()?)?;
+                config.before = lines;
+                config.after = lines;
+            }
+            "--json" => config.json = true,
+            "--color" => {
+                config.color = match value()?.as_str() {
+                    "auto" => ColorChoice::Auto,
+                    "always" => ColorChoice::Always,
+                    "never" => ColorChoice::Never,
+                    other => {
+                        return Err(ConfigError::InvalidValue(
+                            flag.to_string(),
+                            other.to_string(),
+                        ));
+                    }
+                }
+            }
+            "--" => {
+                positional.extend(args.by_ref().cloned());
+                break;
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(ConfigError::UnknownFlag(flag.to_string()));
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    config.query = positional.next().ok_or(ConfigError::MissingQuery)?;
+    config.paths = positional.collect();
+    if config.paths.is_empty() {
+        config.paths.push("-".to_string());
+    }
+
+    Ok(config)
+}
+
+/// Read buffer per input. Lines are streamed through it, so memory stays
+/// flat no matter how large the file is.
+const READ_BUFFER: usize = 64 * 1024;
+
+/// Opens a path for streaming, with `-` meaning stdin.
+fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    if walk::is_stdin(path) {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    let file = File::open(path)?;
+    Ok(Box::new(BufReader::with_capacity(READ_BUFFER, file)))
+}
+
+/// Streams `reader` line by line, handing every line to print to `emit`.
+///
+/// Returns the number of matching lines, or `None` when the input looks
+/// binary. Invalid UTF-8 is replaced rather than rejected so log files with
+/// the odd stray byte are still searchable.
+pub fn search_reader<R: BufRead>(
+    mut reader: R,
+    config: &Config,
+    matcher: &Matcher,
+    mut emit: impl FnMut(Event) -> io::Result<()>,
+) -> io::Result<Option<usize>> {
+    if walk::is_binary(reader.fill_buf()?) {
+        return Ok(None);
+    }
+
+    let mut searcher = ContextSearcher::new(matcher, config.before, config.after);
+    let mut buf = Vec::new();
+    let mut batch = Vec::new();
+    let mut number = 0;
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        number += 1;
+
+        let line = String::from_utf8_lossy(trim_newline(&buf));
+        searcher.push(number, &line, |event| batch.push(event));
+        // Counting never prints lines, so there is no need to keep them.
+        if config.count {
+            batch.clear();
+        }
+        for event in batch.drain(..) {
+            emit(event)?;
+        }
+    }
+
+    Ok(Some(searcher.matches()))
+}
+
+fn trim_newline(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Events a file may have waiting for the printer before its search pauses.
+const FILE_BUFFER: usize = 1024;
+
+/// What a parallel search sends back to the printer for one file.
+enum Message {
+    Event(Event),
+    /// The file is finished: its match count, `None` when it looks binary,
+    /// or why it could not be read.
+    Done(io::Result<Option<usize>>),
+}
+
+/// Searches one file, handing each event to the printer as it is found.
+/// Used when many files are scanned in parallel.
+fn search_file(config: &Config, matcher: &Matcher, path: &Path, tx: &SyncSender<Message>) {
+    let result = open(path).and_then(|reader| {
+        search_reader(reader, config, matcher, |event| {
+            tx.send(Message::Event(event))
+                .map_err(|_| io::ErrorKind::BrokenPipe.into())
+        })
+    });
+    // If the printer has stopped there is nobody left to tell.
+    let _ = tx.send(Message::Done(result));
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    match search_all(&config) {
+        // `minigrep ... | head` closing the pipe early is not an error.
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result.map_err(Into::into),
+    }
+}
+
+fn search_all(config: &Config) -> io::Result<()> {
+    let invalid = |err: &dyn Error| io::Error::new(io::ErrorKind::InvalidInput, err.to_string());
+    let matcher = Matcher::new(&config.query, config.regex, config.ignore_case)
+        .map_err(|err| invalid(&err))?;
+    let filter = FileFilter::new(&config.include, &config.exclude).map_err(|err| invalid(&err))?;
+    let files = walk::collect_files(&config.paths, config.recursive, &filter)
+        .map_err(|err| invalid(err.as_ref()))?;
+    // With several files every result needs to say where it came from.
+    let with_path = config.recursive || config.paths.len() > 1;
+    let color = match config.color {
+        ColorChoice::Auto => io::stdout().is_terminal(),
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    };
+    let mut printer = Printer::new(config, with_path, color);
+    let mut out = io::stdout().lock();
+
+    // A single input (a big log file, or stdin) is streamed straight to stdout.
+    if let [path] = files.as_slice() {
+        let shown = walk::display_path(path);
+        let reader = open(path).map_err(|err| with_context(&shown, err))?;
+        let matches = search_reader(reader, config, &matcher, |event| {
+            printer.event(&mut out, &shown, &event)
+        })?;
+        if let (Some(matches), true) = (matches, config.count) {
+            printer.count(&mut out, &shown, matches)?;
+        }
+        return out.flush();
+    }
+
+    // Files are scanned in parallel but printed in walk order, so the output
+    // is the same from run to run. Each file streams through its own bounded
+    // channel: a search that gets ahead of the printer waits rather than
+    // holding its matches in memory. FIFO spawning starts the searches in
+    // walk order, so the file being printed always has a thread.
+    let (senders, receivers): (Vec<_>, Vec<_>) = files
+        .iter()
+        .map(|_| mpsc::sync_channel(FILE_BUFFER))
+        .unzip();
+    let mut failed = 0;
+    rayon::in_place_scope_fifo(|scope| {
+        for (path, tx) in files.iter().zip(senders) {
+            let matcher = &matcher;
+            scope.spawn_fifo(move |_| search_file(config, matcher, path, &tx));
+        }
+        // Returning early drops the receivers, which stops the searches.
+        for (path, rx) in files.iter().zip(receivers) {
+            let shown = walk::display_path(path);
+            if !print_file(config, &mut printer, &mut out, &shown, rx)? {
+                failed += 1;
+            }
+        }
+        io::Result::Ok(())
+    })?;
+    out.flush()?;
+
+    match failed {
+        0 => Ok(()),
+        1 => Err(io::Error::other("1 file could not be read")),
+        n => Err(io::Error::other(format!("{n} files could not be read"))),
+    }
+}
+
+/// Prints one file's results as its search sends them. Returns `false` if
+/// the file could not be read.
+fn print_file(
+    config: &Config,
+    printer: &mut Printer,
+    out: &mut impl Write,
+    shown: &str,
+    rx: Receiver<Message>,
+) -> io::Result<bool> {
+    let mut started = false;
+    for message in rx {
+        match message {
+            Message::Event(event) => {
+                if !started {
+                    printer.start_file(out)?;
+                    started = true;
+                }
+                printer.event(out, shown, &event)?;
+            }
+            Message::Done(Ok(Some(matches))) if config.count => {
+                printer.count(out, shown, matches)?
+            }
+            Message::Done(Ok(_)) => {}
+            Message::Done(Err(err)) => {
+                eprintln!("minigrep: {shown}: {err}");
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+fn with_context(shown: &str, err: io::Error) -> io::Error {
+    io::Error::new(err.kind(), format!("could not read {shown}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn case_sensitive() {
+        let query = "duct";
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.
+Duct tape.";
+
+        assert_eq!(vec!["safe, fast, productive."], search(query, contents));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let query = "rUsT";
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.
+Trust me.";
+
+        assert_eq!(
+            vec!["Rust:", "Trust me."],
+            search_case_insensitive(query, contents)
+        );
+    }
+
+    #[test]
+    fn regex_with_line_numbers() {
+        let config = parse_config(&args(&[
+            "minigrep",
+            "-E",
+            "-i",
+            r"^(are|then)\b",
+            "poem.txt",
+        ]))
+        .unwrap();
+        let matcher = Matcher::new(&config.query, config.regex, config.ignore_case).unwrap();
+        let contents = "I'm nobody!\nAre you nobody, too?\nThen there's a pair of us";
+
+        assert_eq!(
+            vec![
+                (2, "Are you nobody, too?"),
+                (3, "Then there's a pair of us")
+            ],
+            search_lines(&matcher, contents)
+        );
+    }
+
+    #[test]
+    fn parse_config_reports_missing_arguments() {
+        assert_eq!(
+            parse_config(&args(&["minigrep"])).err(),
+            Some(ConfigError::MissingQuery)
+        );
+        assert_eq!(
+            parse_config(&args(&["minigrep", "-x", "frog", "poem.txt"])).err(),
+            Some(ConfigError::UnknownFlag("-x".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_config_reads_flags() {
+        let config = parse_config(&args(&["minigrep", "-n", "-c", "frog", "poem.txt"])).unwrap();
+        assert_eq!(config.query, "frog");
+        assert_eq!(config.paths, vec!["poem.txt"]);
+        assert!(config.line_numbers && config.count && !config.regex);
+    }
+
+    #[test]
+    fn parse_config_reads_globs_and_many_paths() {
+        let config = parse_config(&args(&[
+            "minigrep",
+            "-r",
+            "--include",
+            "*.rs",
+            "--exclude=tests/**",
+            "--",
+            "-> Self",
+            "src",
+            "tests",
+        ]))
+        .unwrap();
+        assert!(config.recursive);
+        assert_eq!(config.query, "-> Self");
+        assert_eq!(config.paths, vec!["src", "tests"]);
+        assert_eq!(config.include, vec!["*.rs"]);
+        assert_eq!(config.exclude, vec!["tests/**"]);
+
+        assert_eq!(
+            parse_config(&args(&["minigrep", "frog", "--include"])).err(),
+            Some(ConfigError::MissingValue("--include".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_config_defaults_to_stdin() {
+        let config = parse_config(&args(&["minigrep", "-n", "panicked"])).unwrap();
+        assert_eq!(config.paths, vec!["-"]);
+    }
+
+    #[test]
+    fn search_reader_streams_lines() {
+        let config = parse_config(&args(&["minigrep", "-c", "FAILED", "-"])).unwrap();
+        let matcher = Matcher::new(&config.query, config.regex, config.ignore_case).unwrap();
+        let log: &[u8] = b"test a ... ok\r\ntest b ... FAILED\r\n\xff\xfe FAILED\n";
+
+        let mut seen = 0;
+        let matches = search_reader(log, &config, &matcher, |_| {
+            seen += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(matches, Some(2));
+        assert_eq!(seen, 0, "count mode prints no lines");
+
+        let config = parse_config(&args(&["minigrep", "FAILED"])).unwrap();
+        let mut lines = Vec::new();
+        search_reader(log, &config, &matcher, |event| {
+            if let Event::Line(line) = event {
+                lines.push(line.text);
+            }
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(lines, vec!["test b ... FAILED", "\u{fffd}\u{fffd} FAILED"]);
+
+        let binary: &[u8] = b"ELF\x00FAILED";
+        assert_eq!(
+            search_reader(binary, &config, &matcher, |_| Ok(())).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn run_fails_when_a_file_cannot_be_read() {
+        let config = parse_config(&args(&[
+            "minigrep",
+            "xyzzy",
+            "name.txt",
+            "no-such-file.txt",
+            "Cargo.toml",
+        ]))
+        .unwrap();
+        let err = run(config).unwrap_err();
+        assert_eq!(err.to_string(), "1 file could not be read");
+
+        let config = parse_config(&args(&["minigrep", "xyzzy", "name.txt", "Cargo.toml"])).unwrap();
+        assert!(run(config).is_ok());
+    }
+
+    #[test]
+    fn parse_config_reads_context_and_output_flags() {
+        let config = parse_config(&args(&[
+            "minigrep",
+            "-B2",
+            "-A",
+            "1",
+            "--json",
+            "--color=never",
+            "frog",
+            "poem.txt",
+        ]))
+        .unwrap();
+        assert_eq!((config.before, config.after), (2, 1));
+        assert!(config.json);
+        assert_eq!(config.color, ColorChoice::Never);
+
+        let config = parse_config(&args(&[
+            "minigrep", "-C", "3", "-A", "1", "frog", "poem.txt",
+        ]))
+        .unwrap();
+        assert_eq!((config.before, config.after), (3, 1));
+
+        assert_eq!(
+            parse_config(&args(&["minigrep", "-C", "lots", "frog", "poem.txt"])).err(),
+            Some(ConfigError::InvalidValue(
+                "-C".to_string(),
+                "lots".to_string()
+            ))
+        );
+    }
+}
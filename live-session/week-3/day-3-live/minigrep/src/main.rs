@@ -1,20 +1,18 @@
-use std::{env, fs};
+use std::{env, process};
 
-struct Config {
-    query: String,
-    file_path: String,
-}
+use minigrep::{USAGE, parse_config};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    let config = parse_config(&args);
-    let content = fs::read_to_string(config.file_path).expect("Should be able to read the file.");
-}
-
-fn parse_config(args: &[String]) -> Config {
-    let query = args[1].clone();
-    let file_path = args[2].clone();
+    let config = parse_config(&args).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        eprintln!("{USAGE}");
+        process::exit(2);
+    });
 
-    Config { query, file_path }
+    if let Err(err) = minigrep::run(config) {
+        eprintln!("Application error: {err}");
+        process::exit(1);
+    }
 }
@@ -0,0 +1,210 @@
+use crate::{
+    Config,
+    search::{Event, Line},
+};
+use serde::Serialize;
+use std::io::{self, Write};
+
+const HIGHLIGHT: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+/// How results are written to stdout.
+pub struct Printer {
+    /// Prefix every line with its path (`path:line:text`).
+    pub with_path: bool,
+    pub line_numbers: bool,
+    /// Highlight matched spans with ANSI colours.
+    pub color: bool,
+    /// One JSON record per line instead of text.
+    pub json: bool,
+    /// Print `--` between files, as context output does between blocks.
+    pub separate_files: bool,
+    printed_any: bool,
+}
+
+#[derive(Serialize)]
+struct Span {
+    start: usize,
+    end: usize,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<'a> {
+    Match {
+        path: &'a str,
+        line_number: usize,
+        text: &'a str,
+        matches: Vec<Span>,
+    },
+    Context {
+        path: &'a str,
+        line_number: usize,
+        text: &'a str,
+    },
+    Count {
+        path: &'a str,
+        count: usize,
+    },
+}
+
+impl Printer {
+    pub fn new(config: &Config, with_path: bool, color: bool) -> Self {
+        Printer {
+            with_path,
+            line_numbers: config.line_numbers,
+            color: color && !config.json,
+            json: config.json,
+            separate_files: config.before > 0 || config.after > 0,
+            printed_any: false,
+        }
+    }
+
+    /// Marks the start of a new file's results.
+    pub fn start_file(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.separate_files && self.printed_any && !self.json {
+            writeln!(out, "--")?;
+        }
+        Ok(())
+    }
+
+    pub fn event(&mut self, out: &mut impl Write, path: &str, event: &Event) -> io::Result<()> {
+        match event {
+            Event::Break if self.json => Ok(()),
+            Event::Break => writeln!(out, "--"),
+            Event::Line(line) => {
+                self.printed_any = true;
+                match self.json {
+                    true => self.json_line(out, path, line),
+                    false => self.text_line(out, path, line),
+                }
+            }
+        }
+    }
+
+    pub fn count(&mut self, out: &mut impl Write, path: &str, count: usize) -> io::Result<()> {
+        self.printed_any = true;
+        if self.json {
+            let record = Record::Count { path, count };
+            writeln!(out, "{}", serde_json::to_string(&record)?)
+        } else if self.with_path {
+            writeln!(out, "{path}:{count}")
+        } else {
+            writeln!(out, "{count}")
+        }
+    }
+
+    fn json_line(&self, out: &mut impl Write, path: &str, line: &Line) -> io::Result<()> {
+        let record = match line.is_match {
+            true => Record::Match {
+                path,
+                line_number: line.number,
+                text: &line.text,
+                matches: line
+                    .spans
+                    .iter()
+                    .map(|&(start, end)| Span { start, end })
+                    .collect(),
+            },
+            false => Record::Context {
+                path,
+                line_number: line.number,
+                text: &line.text,
+            },
+        };
+        writeln!(out, "{}", serde_json::to_string(&record)?)
+    }
+
+    fn text_line(&self, out: &mut impl Write, path: &str, line: &Line) -> io::Result<()> {
+        // grep style: `:` after the prefix of a match, `-` after context.
+        let sep = if line.is_match { ':' } else { '-' };
+        if self.with_path {
+            write!(out, "{path}{sep}{}{sep}", line.number)?;
+        } else if self.line_numbers {
+            write!(out, "{}{sep}", line.number)?;
+        }
+
+        if !self.color || line.spans.is_empty() {
+            return writeln!(out, "{}", line.text);
+        }
+        let mut last = 0;
+        for &(start, end) in &line.spans {
+            write!(
+                out,
+                "{}{HIGHLIGHT}{}{RESET}",
+                &line.text[last..start],
+                &line.text[start..end]
+            )?;
+            last = end;
+        }
+        writeln!(out, "{}", &line.text[last..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_config;
+
+    fn printer(flags: &[&str], with_path: bool, color: bool) -> Printer {
+        let mut args = vec!["minigrep".to_string()];
+        args.extend(flags.iter().map(|f| f.to_string()));
+        args.extend(["auth".to_string(), "src/lib.rs".to_string()]);
+        Printer::new(&parse_config(&args).unwrap(), with_path, color)
+    }
+
+    fn matched(number: usize, text: &str, spans: Vec<(usize, usize)>) -> Event {
+        Event::Line(Line {
+            number,
+            text: text.to_string(),
+            is_match: true,
+            spans,
+        })
+    }
+
+    fn print(printer: &mut Printer, events: &[Event]) -> String {
+        let mut out = Vec::new();
+        for event in events {
+            printer.event(&mut out, "src/lib.rs", event).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn highlights_matched_spans() {
+        let mut printer = printer(&["-n"], false, true);
+        let out = print(
+            &mut printer,
+            &[matched(7, "to.require_auth();", vec![(3, 15)])],
+        );
+        assert_eq!(out, "7:to.\x1b[1;31mrequire_auth\x1b[0m();\n");
+    }
+
+    #[test]
+    fn json_emits_one_record_per_line() {
+        let mut printer = printer(&["--json", "-B1"], true, true);
+        let context = Event::Line(Line {
+            number: 6,
+            text: "pub fn transfer(env: Env, from: Address) {".to_string(),
+            is_match: false,
+            spans: Vec::new(),
+        });
+        let out = print(
+            &mut printer,
+            &[
+                context,
+                matched(7, "from.require_auth();", vec![(5, 17)]),
+                Event::Break,
+            ],
+        );
+        assert_eq!(
+            out,
+            concat!(
+                r#"{"type":"context","path":"src/lib.rs","line_number":6,"text":"pub fn transfer(env: Env, from: Address) {"}"#,
+                "\n",
+                r#"{"type":"match","path":"src/lib.rs","line_number":7,"text":"from.require_auth();","matches":[{"start":5,"end":17}]}"#,
+                "\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use regex::{Regex, RegexBuilder};
+use std::collections::VecDeque;
+
+/// Decides whether a line matches the query and where.
+pub enum Matcher {
+    Plain(String),
+    /// Plain and case-insensitive queries both compile down to a regex, so
+    /// highlighted spans line up with the original text.
+    Regex(Regex),
+}
+
+impl Matcher {
+    pub fn new(query: &str, regex: bool, ignore_case: bool) -> Result<Matcher, regex::Error> {
+        if !regex && !ignore_case {
+            return Ok(Matcher::Plain(query.to_string()));
+        }
+
+        let pattern = match regex {
+            true => query.to_string(),
+            false => regex::escape(query),
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()?;
+        Ok(Matcher::Regex(regex))
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        match self {
+            Matcher::Plain(query) => line.contains(query.as_str()),
+            Matcher::Regex(regex) => regex.is_match(line),
+        }
+    }
+
+    /// Byte ranges of every non-empty match in `line`.
+    pub fn spans(&self, line: &str) -> Vec<(usize, usize)> {
+        match self {
+            Matcher::Plain(query) if query.is_empty() => Vec::new(),
+            Matcher::Plain(query) => line
+                .match_indices(query.as_str())
+                .map(|(start, hit)| (start, start + hit.len()))
+                .collect(),
+            Matcher::Regex(regex) => regex
+                .find_iter(line)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+        }
+    }
+}
+
+pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+    let matcher = Matcher::Plain(query.to_string());
+    search_lines(&matcher, contents)
+        .into_iter()
+        .map(|(_, line)| line)
+        .collect()
+}
+
+pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+    let matcher = Matcher::new(query, false, true).expect("an escaped query is a valid regex");
+    search_lines(&matcher, contents)
+        .into_iter()
+        .map(|(_, line)| line)
+        .collect()
+}
+
+/// Matching lines together with their 1-based line numbers.
+pub fn search_lines<'a>(matcher: &Matcher, contents: &'a str) -> Vec<(usize, &'a str)> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| matcher.is_match(line))
+        .map(|(i, line)| (i + 1, line))
+        .collect()
+}
+
+/// A line worth printing: either a match or context around one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub number: usize,
+    pub text: String,
+    pub is_match: bool,
+    /// Byte ranges to highlight; empty for context lines.
+    pub spans: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Line(Line),
+    /// Gap between two non-adjacent blocks of output, printed as `--`.
+    Break,
+}
+
+/// Feeds lines through a matcher one at a time, keeping just enough of the
+/// recent past to print `before` lines of context.
+pub struct ContextSearcher<'m> {
+    matcher: &'m Matcher,
+    before: usize,
+    after: usize,
+    pending: VecDeque<(usize, String)>,
+    after_left: usize,
+    last_emitted: Option<usize>,
+    matches: usize,
+}
+
+impl<'m> ContextSearcher<'m> {
+    pub fn new(matcher: &'m Matcher, before: usize, after: usize) -> Self {
+        ContextSearcher {
+            matcher,
+            before,
+            after,
+            pending: VecDeque::new(),
+            after_left: 0,
+            last_emitted: None,
+            matches: 0,
+        }
+    }
+
+    /// Number of matching lines seen so far.
+    pub fn matches(&self) -> usize {
+        self.matches
+    }
+
+    pub fn push(&mut self, number: usize, text: &str, mut emit: impl FnMut(Event)) {
+        if self.matcher.is_match(text) {
+            let first = self.pending.front().map_or(number, |(n, _)| *n);
+            let has_context = self.before > 0 || self.after > 0;
+            if has_context && self.last_emitted.is_some_and(|last| first > last + 1) {
+                emit(Event::Break);
+            }
+            for (number, text) in self.pending.drain(..) {
+                emit(Event::Line(Line {
+                    number,
+                    text,
+                    is_match: false,
+                    spans: Vec::new(),
+                }));
+            }
+            emit(Event::Line(Line {
+                number,
+                text: text.to_string(),
+                is_match: true,
+                spans: self.matcher.spans(text),
+            }));
+            self.matches += 1;
+            self.after_left = self.after;
+            self.last_emitted = Some(number);
+        } else if self.after_left > 0 {
+            emit(Event::Line(Line {
+                number,
+                text: text.to_string(),
+                is_match: false,
+                spans: Vec::new(),
+            }));
+            self.after_left -= 1;
+            self.last_emitted = Some(number);
+        } else if self.before > 0 {
+            if self.pending.len() == self.before {
+                self.pending.pop_front();
+            }
+            self.pending.push_back((number, text.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(matcher: &Matcher, before: usize, after: usize, contents: &str) -> Vec<String> {
+        let mut searcher = ContextSearcher::new(matcher, before, after);
+        let mut out = Vec::new();
+        for (i, line) in contents.lines().enumerate() {
+            searcher.push(i + 1, line, |event| {
+                out.push(match event {
+                    Event::Line(line) if line.is_match => format!("{}:{}", line.number, line.text),
+                    Event::Line(line) => format!("{}-{}", line.number, line.text),
+                    Event::Break => "--".to_string(),
+                })
+            });
+        }
+        out
+    }
+
+    #[test]
+    fn context_lines_and_breaks() {
+        let matcher = Matcher::Plain("x".to_string());
+        let contents = "a\nb\nx1\nc\nd\ne\nf\nx2\nx3\ng";
+
+        assert_eq!(
+            run(&matcher, 1, 1, contents),
+            vec!["2-b", "3:x1", "4-c", "--", "7-f", "8:x2", "9:x3", "10-g"]
+        );
+        assert_eq!(run(&matcher, 0, 0, contents), vec!["3:x1", "8:x2", "9:x3"]);
+    }
+
+    #[test]
+    fn overlapping_context_is_not_repeated() {
+        let matcher = Matcher::Plain("x".to_string());
+        assert_eq!(
+            run(&matcher, 2, 2, "x1\na\nx2\nb"),
+            vec!["1:x1", "2-a", "3:x2", "4-b"]
+        );
+    }
+
+    #[test]
+    fn spans_follow_original_text() {
+        let matcher = Matcher::new("ß", false, true).unwrap();
+        assert_eq!(matcher.spans("Straße ß"), vec![(4, 6), (8, 10)]);
+
+        let matcher = Matcher::new("require_auth", false, false).unwrap();
+        assert_eq!(
+            matcher.spans("from.require_auth(); to.require_auth();"),
+            vec![(5, 17), (24, 36)]
+        );
+    }
+}
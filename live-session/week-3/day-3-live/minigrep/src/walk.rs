@@ -0,0 +1,192 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::{
+    collections::HashSet,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Build output and VCS metadata are never worth searching.
+const SKIPPED_DIRS: &[&str] = &["target", ".git"];
+
+/// How many leading bytes are checked when sniffing for binary files.
+const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+/// `--include` / `--exclude` globs applied while walking directories.
+///
+/// A glob matches against the whole path (without a leading `./`) or just
+/// the file name, so `*.rs`, `main.rs` and `submissions/**/src/*.rs` all work.
+pub struct FileFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl FileFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, globset::Error> {
+        let include = match include.is_empty() {
+            true => None,
+            false => Some(build_set(include)?),
+        };
+        Ok(FileFilter {
+            include,
+            exclude: build_set(exclude)?,
+        })
+    }
+
+    fn excluded(&self, path: &Path) -> bool {
+        matches(&self.exclude, path)
+    }
+
+    fn included(&self, path: &Path) -> bool {
+        self.include.as_ref().is_none_or(|set| matches(set, path))
+    }
+}
+
+fn build_set(globs: &[String]) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob)?);
+    }
+    builder.build()
+}
+
+fn matches(set: &GlobSet, path: &Path) -> bool {
+    set.is_match(display_path(path)) || path.file_name().is_some_and(|name| set.is_match(name))
+}
+
+/// `-` on the command line stands for standard input.
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// The path as printed in results, without a leading `./`.
+pub fn display_path(path: &Path) -> String {
+    if is_stdin(path) {
+        return "(standard input)".to_string();
+    }
+    let shown = path.to_string_lossy();
+    shown.strip_prefix("./").unwrap_or(&shown).to_string()
+}
+
+/// Treats a file as binary if its first few kilobytes contain a NUL byte.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(BINARY_SNIFF_LEN).any(|&b| b == 0)
+}
+
+/// Expands the command line paths into the list of files to search.
+///
+/// Files named on the command line are always searched. Directories need
+/// `recursive`, and while walking them `target/`, `.git/` and anything
+/// excluded are skipped and only included files are kept. Symlinked
+/// directories are followed, but each directory is only walked once, so a
+/// link back to an ancestor cannot loop.
+pub fn collect_files(
+    paths: &[String],
+    recursive: bool,
+    filter: &FileFilter,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    for path in paths {
+        let path = PathBuf::from(path);
+        if !path.is_dir() {
+            files.push(path);
+        } else if recursive {
+            walk_dir(&path, filter, &mut files, &mut visited);
+        } else {
+            return Err(format!("{} is a directory (use -r to search it)", path.display()).into());
+        }
+    }
+    Ok(files)
+}
+
+/// `visited` holds the canonical path of every directory walked so far.
+fn walk_dir(
+    dir: &Path,
+    filter: &FileFilter,
+    files: &mut Vec<PathBuf>,
+    visited: &mut HashSet<PathBuf>,
+) {
+    match fs::canonicalize(dir) {
+        Ok(canonical) => {
+            if !visited.insert(canonical) {
+                return;
+            }
+        }
+        Err(err) => {
+            eprintln!("minigrep: {}: {err}", display_path(dir));
+            return;
+        }
+    }
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("minigrep: {}: {err}", display_path(dir));
+            return;
+        }
+    };
+
+    // Sorted so results come out in the same order on every platform.
+    let mut entries: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    entries.sort();
+
+    for path in entries {
+        if filter.excluded(&path) {
+            continue;
+        }
+        // `file_type` on the entry would not follow symlinks; `is_dir` does.
+        if path.is_dir() {
+            let skipped = path
+                .file_name()
+                .is_some_and(|name| SKIPPED_DIRS.iter().any(|skip| name == *skip));
+            if !skipped {
+                walk_dir(&path, filter, files, visited);
+            }
+        } else if filter.included(&path) {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs_match_paths_and_file_names() {
+        let filter = FileFilter::new(
+            &[
+                "submissions/**/src/*.rs".to_string(),
+                "Cargo.toml".to_string(),
+            ],
+            &["**/tests/**".to_string()],
+        )
+        .unwrap();
+
+        assert!(filter.included(Path::new("./submissions/week-1/todo/src/main.rs")));
+        assert!(filter.included(Path::new("live-session/minigrep/Cargo.toml")));
+        assert!(!filter.included(Path::new("submissions/week-1/todo/README.md")));
+        assert!(filter.excluded(Path::new("todo/tests/api.rs")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_loops_are_walked_once() {
+        let root = std::env::temp_dir().join(format!("minigrep_walk_{}", std::process::id()));
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("sub/notes.txt"), "frog\n").unwrap();
+        std::os::unix::fs::symlink("..", root.join("sub/up")).unwrap();
+
+        let filter = FileFilter::new(&[], &[]).unwrap();
+        let files = collect_files(&[root.display().to_string()], true, &filter);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(files.unwrap(), vec![root.join("sub/notes.txt")]);
+    }
+
+    #[test]
+    fn nul_bytes_mean_binary() {
+        assert!(is_binary(b"\x7fELF\x00\x01"));
+        assert!(!is_binary("safe, fast, productive.".as_bytes()));
+    }
+}
@@ -0,0 +1,142 @@
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, vec, Address, Env, String,
+    Symbol, Vec,
+};
+
+const DAY_IN_LEDGERS: u32 = 17280;
+const GREETING_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
+const GREETING_LIFETIME_THRESHOLD: u32 = GREETING_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// Part of the day, going by the ledger's UTC clock.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeOfDay {
+    /// 05:00 to 11:59.
+    Morning,
+    /// 12:00 to 17:59.
+    Afternoon,
+    /// 18:00 to 04:59.
+    Evening,
+}
+
+impl TimeOfDay {
+    pub fn at(timestamp: u64) -> TimeOfDay {
+        match timestamp % 86_400 / 3_600 {
+            5..=11 => TimeOfDay::Morning,
+            12..=17 => TimeOfDay::Afternoon,
+            _ => TimeOfDay::Evening,
+        }
+    }
+}
+
+#[contracttype]
+enum DataKey {
+    Admin,
+    /// Language used when the requested one has no greeting.
+    Fallback,
+    Greeting(Symbol, TimeOfDay),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum GreetingError {
+    /// Neither the requested language nor the fallback has a greeting for
+    /// this time of day.
+    NoGreeting = 1,
+}
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    /// Sets the admin who manages greetings, with English as the fallback
+    /// language.
+    pub fn __constructor(env: Env, admin: Address) {
+        let en = symbol_short!("en");
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Fallback, &en);
+        for (time, greeting) in [
+            (TimeOfDay::Morning, "Good morning"),
+            (TimeOfDay::Afternoon, "Good afternoon"),
+            (TimeOfDay::Evening, "Good evening"),
+        ] {
+            Self::store(&env, en.clone(), time, &String::from_str(&env, greeting));
+        }
+        Self::bump_instance(&env);
+    }
+
+    /// Greets `to` in `lang` for the current time of day, falling back to the
+    /// fallback language when `lang` has no greeting for it.
+    pub fn hello(env: Env, to: String, lang: Symbol) -> Result<Vec<String>, GreetingError> {
+        Self::bump_instance(&env);
+        let time = TimeOfDay::at(env.ledger().timestamp());
+        let greeting = Self::greeting(env.clone(), lang, time)
+            .or_else(|| Self::greeting(env.clone(), Self::fallback(env.clone()), time))
+            .ok_or(GreetingError::NoGreeting)?;
+        Ok(vec![&env, greeting, to])
+    }
+
+    /// Reads a greeting, keeping it alive while it is in use.
+    pub fn greeting(env: Env, lang: Symbol, time: TimeOfDay) -> Option<String> {
+        let key = DataKey::Greeting(lang, time);
+        let greeting = env.storage().persistent().get(&key)?;
+        Self::bump(&env, &key);
+        Some(greeting)
+    }
+
+    /// Adds or replaces a greeting. Admin only.
+    pub fn set_greeting(env: Env, lang: Symbol, time: TimeOfDay, greeting: String) {
+        Self::admin(env.clone()).require_auth();
+        Self::store(&env, lang, time, &greeting);
+        Self::bump_instance(&env);
+    }
+
+    /// Removes a greeting. Admin only.
+    pub fn remove_greeting(env: Env, lang: Symbol, time: TimeOfDay) {
+        Self::admin(env.clone()).require_auth();
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Greeting(lang, time));
+        Self::bump_instance(&env);
+    }
+
+    pub fn fallback(env: Env) -> Symbol {
+        env.storage().instance().get(&DataKey::Fallback).unwrap()
+    }
+
+    /// Changes the fallback language. Admin only.
+    pub fn set_fallback(env: Env, lang: Symbol) {
+        Self::admin(env.clone()).require_auth();
+        env.storage().instance().set(&DataKey::Fallback, &lang);
+        Self::bump_instance(&env);
+    }
+
+    pub fn admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+}
+
+impl Contract {
+    fn store(env: &Env, lang: Symbol, time: TimeOfDay, greeting: &String) {
+        let key = DataKey::Greeting(lang, time);
+        env.storage().persistent().set(&key, greeting);
+        Self::bump(env, &key);
+    }
+
+    fn bump(env: &Env, key: &DataKey) {
+        env.storage().persistent().extend_ttl(
+            key,
+            GREETING_LIFETIME_THRESHOLD,
+            GREETING_BUMP_AMOUNT,
+        );
+    }
+
+    /// Keeps the admin and fallback alive for as long as the greetings.
+    fn bump_instance(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(GREETING_LIFETIME_THRESHOLD, GREETING_BUMP_AMOUNT);
+    }
+}
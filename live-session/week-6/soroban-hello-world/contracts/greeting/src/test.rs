@@ -0,0 +1,151 @@
+#![cfg(test)]
+
+use crate::contract::{Contract, ContractClient, GreetingError, TimeOfDay};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+const MORNING: u64 = 8 * 3_600;
+const AFTERNOON: u64 = 14 * 3_600;
+const EVENING: u64 = 21 * 3_600;
+
+fn setup() -> (Env, ContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let contract_id = env.register(Contract, (&admin,));
+    let client = ContractClient::new(&env, &contract_id);
+
+    (env, client, admin)
+}
+
+fn at(env: &Env, timestamp: u64) {
+    env.ledger().with_mut(|ledger| ledger.timestamp = timestamp);
+}
+
+#[test]
+fn test_time_of_day_boundaries() {
+    assert_eq!(TimeOfDay::at(4 * 3_600 + 3_599), TimeOfDay::Evening);
+    assert_eq!(TimeOfDay::at(5 * 3_600), TimeOfDay::Morning);
+    assert_eq!(TimeOfDay::at(12 * 3_600), TimeOfDay::Afternoon);
+    assert_eq!(TimeOfDay::at(18 * 3_600), TimeOfDay::Evening);
+    // The day wraps: 08:00 on a later day is still morning.
+    assert_eq!(TimeOfDay::at(10 * 86_400 + MORNING), TimeOfDay::Morning);
+}
+
+#[test]
+fn test_english_greetings_by_time_of_day() {
+    let (env, client, _) = setup();
+    let to = String::from_str(&env, "Dev");
+    let en = symbol_short!("en");
+
+    for (time, greeting) in [
+        (MORNING, "Good morning"),
+        (AFTERNOON, "Good afternoon"),
+        (EVENING, "Good evening"),
+    ] {
+        at(&env, time);
+        assert_eq!(
+            client.hello(&to, &en),
+            vec![&env, String::from_str(&env, greeting), to.clone()]
+        );
+    }
+}
+
+#[test]
+fn test_admin_adds_a_language() {
+    let (env, client, admin) = setup();
+    let to = String::from_str(&env, "Dev");
+    let yo = symbol_short!("yo");
+    let e_kaaro = String::from_str(&env, "E kaaro");
+
+    client.set_greeting(&yo, &TimeOfDay::Morning, &e_kaaro);
+    assert_eq!(env.auths()[0].0, admin);
+
+    at(&env, MORNING);
+    assert_eq!(client.hello(&to, &yo), vec![&env, e_kaaro, to.clone()]);
+}
+
+#[test]
+fn test_missing_translation_falls_back() {
+    let (env, client, _) = setup();
+    let to = String::from_str(&env, "Dev");
+    let fr = symbol_short!("fr");
+    client.set_greeting(&fr, &TimeOfDay::Morning, &String::from_str(&env, "Bonjour"));
+
+    // French has no evening greeting, so English is used.
+    at(&env, EVENING);
+    assert_eq!(
+        client.hello(&to, &fr).get(0).unwrap(),
+        String::from_str(&env, "Good evening")
+    );
+    // A language with nothing at all falls back too.
+    assert_eq!(
+        client.hello(&to, &symbol_short!("ha")).get(0).unwrap(),
+        String::from_str(&env, "Good evening")
+    );
+
+    // Switching the fallback to French leaves evenings without a greeting.
+    client.set_fallback(&fr);
+    assert_eq!(
+        client.try_hello(&to, &symbol_short!("ha")),
+        Err(Ok(GreetingError::NoGreeting))
+    );
+    at(&env, MORNING);
+    assert_eq!(
+        client.hello(&to, &symbol_short!("ha")).get(0).unwrap(),
+        String::from_str(&env, "Bonjour")
+    );
+}
+
+#[test]
+fn test_removed_greeting_is_missing() {
+    let (env, client, _) = setup();
+    let en = symbol_short!("en");
+    client.remove_greeting(&en, &TimeOfDay::Afternoon);
+
+    at(&env, AFTERNOON);
+    assert_eq!(client.greeting(&en, &TimeOfDay::Afternoon), None);
+    assert_eq!(
+        client.try_hello(&String::from_str(&env, "Dev"), &en),
+        Err(Ok(GreetingError::NoGreeting))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_only_admin_sets_greetings() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, (Address::generate(&env),));
+    let client = ContractClient::new(&env, &contract_id);
+
+    client.set_greeting(
+        &symbol_short!("en"),
+        &TimeOfDay::Morning,
+        &String::from_str(&env, "Hi"),
+    );
+}
+
+#[test]
+fn test_greetings_stay_alive_while_used() {
+    let (env, client, admin) = setup();
+    let to = String::from_str(&env, "Dev");
+    let en = symbol_short!("en");
+
+    // Greeted every 60 days for a year, long past the 90 days a greeting
+    // lives after it was set.
+    for day in 1..=6 {
+        env.ledger().with_mut(|ledger| {
+            ledger.sequence_number += 60 * 17280;
+            ledger.timestamp = day * 86_400 + MORNING;
+        });
+        assert_eq!(
+            client.hello(&to, &en).get(0).unwrap(),
+            String::from_str(&env, "Good morning")
+        );
+    }
+    assert_eq!(client.admin(), admin);
+    assert_eq!(client.fallback(), en);
+}
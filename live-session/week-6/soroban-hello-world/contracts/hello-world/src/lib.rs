@@ -1,4 +1,3 @@
 #![no_std]
-mod contract;
-mod storage;
+pub mod storage;
 mod test;
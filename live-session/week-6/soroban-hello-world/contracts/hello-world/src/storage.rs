@@ -1,21 +1,144 @@
-use soroban_sdk::{contract, contractimpl, log, symbol_short, Env, Symbol};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env,
+};
 
-const COUNTER: Symbol = symbol_short!("COUNTER");
+const DAY_IN_LEDGERS: u32 = 17280;
+const COUNTER_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const COUNTER_LIFETIME_THRESHOLD: u32 = COUNTER_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// How much `increment` and `decrement` move a counter.
+    pub step: u32,
+    /// No counter may go above this.
+    pub max: u32,
+}
+
+#[contracttype]
+enum DataKey {
+    Admin,
+    Config,
+    Counter(Address),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CounterError {
+    /// The step must be at least 1 and no larger than the max.
+    InvalidConfig = 1,
+    /// The counter would go above the configured max.
+    AboveMax = 2,
+    /// The counter would go below zero.
+    BelowZero = 3,
+}
 
 #[contract]
 pub struct IncrementContract;
 
 #[contractimpl]
 impl IncrementContract {
-    /// Increment increments an internal counter, and returns the value.
-    pub fn increment(env: Env) -> u32 {
-        let mut count: u32 = env.storage().instance().get(&COUNTER).unwrap_or(0);
-        log!(&env, "count: {}", count);
+    /// Sets the admin who may change the step and max. Counters start with a
+    /// step of 1 and no practical max.
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(
+            &DataKey::Config,
+            &Config {
+                step: 1,
+                max: u32::MAX,
+            },
+        );
+        Self::bump_instance(&env);
+    }
 
-        count += 1;
-        env.storage().instance().set(&COUNTER, &count);
-        env.storage().instance().extend_ttl(50, 100);
+    /// Increment adds the step to `user`'s counter, and returns the value.
+    pub fn increment(env: Env, user: Address) -> Result<u32, CounterError> {
+        user.require_auth();
+        let config = Self::config(env.clone());
+        let count = Self::count(env.clone(), user.clone())
+            .checked_add(config.step)
+            .filter(|count| *count <= config.max)
+            .ok_or(CounterError::AboveMax)?;
+
+        Self::store(&env, &user, count);
+        env.events()
+            .publish((symbol_short!("increment"), user), count);
+        Ok(count)
+    }
+
+    /// Decrement takes the step off `user`'s counter, and returns the value.
+    pub fn decrement(env: Env, user: Address) -> Result<u32, CounterError> {
+        user.require_auth();
+        let config = Self::config(env.clone());
+        let count = Self::count(env.clone(), user.clone())
+            .checked_sub(config.step)
+            .ok_or(CounterError::BelowZero)?;
+
+        Self::store(&env, &user, count);
+        env.events()
+            .publish((symbol_short!("decrement"), user), count);
+        Ok(count)
+    }
+
+    /// Reset puts `user`'s counter back to zero.
+    pub fn reset(env: Env, user: Address) {
+        user.require_auth();
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Counter(user.clone()));
+        Self::bump_instance(&env);
+        env.events().publish((symbol_short!("reset"), user), 0_u32);
+    }
+
+    pub fn count(env: Env, user: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Counter(user))
+            .unwrap_or(0)
+    }
+
+    /// Changes the step and max for every counter. Admin only. Counters
+    /// already above the new max keep their value but can only go down.
+    pub fn set_config(env: Env, step: u32, max: u32) -> Result<(), CounterError> {
+        Self::admin(env.clone()).require_auth();
+        if step == 0 || step > max {
+            return Err(CounterError::InvalidConfig);
+        }
+
+        let config = Config { step, max };
+        env.storage().instance().set(&DataKey::Config, &config);
+        Self::bump_instance(&env);
+        env.events().publish((symbol_short!("config"),), config);
+        Ok(())
+    }
+
+    pub fn config(env: Env) -> Config {
+        env.storage().instance().get(&DataKey::Config).unwrap()
+    }
+
+    pub fn admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+}
+
+impl IncrementContract {
+    fn store(env: &Env, user: &Address, count: u32) {
+        let key = DataKey::Counter(user.clone());
+        env.storage().persistent().set(&key, &count);
+        env.storage().persistent().extend_ttl(
+            &key,
+            COUNTER_LIFETIME_THRESHOLD,
+            COUNTER_BUMP_AMOUNT,
+        );
+        Self::bump_instance(env);
+    }
 
-        count
+    /// Keeps the admin and config alive for as long as the counters.
+    fn bump_instance(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(COUNTER_LIFETIME_THRESHOLD, COUNTER_BUMP_AMOUNT);
     }
 }
@@ -1,15 +1,183 @@
 #![cfg(test)]
 
-use crate::storage::{IncrementContract, IncrementContractClient};
+use crate::storage::{Config, CounterError, IncrementContract, IncrementContractClient};
 
-use super::*;
-use soroban_sdk::{vec, Env, String};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, AuthorizedFunction, Events, Ledger},
+    vec, Address, Env, FromVal, IntoVal, Symbol, Val, Vec,
+};
+
+fn setup() -> (Env, IncrementContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let contract_id = env.register(IncrementContract, (&admin,));
+    let client = IncrementContractClient::new(&env, &contract_id);
+
+    (env, client, admin)
+}
+
+#[test]
+fn test_counters_are_per_user() {
+    let (env, client, _) = setup();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    assert_eq!(client.increment(&alice), 1);
+    assert_eq!(client.increment(&alice), 2);
+    assert_eq!(client.increment(&bob), 1);
+
+    assert_eq!(client.count(&alice), 2);
+    assert_eq!(client.count(&bob), 1);
+}
 
 #[test]
-fn test() {
+fn test_increment_requires_user_auth() {
+    let (env, client, _) = setup();
+    let alice = Address::generate(&env);
+
+    client.increment(&alice);
+
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    let (signer, invocation) = &auths[0];
+    assert_eq!(signer, &alice);
+    assert_eq!(
+        invocation.function,
+        AuthorizedFunction::Contract((
+            client.address.clone(),
+            Symbol::new(&env, "increment"),
+            vec![&env, alice.into_val(&env)],
+        ))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_increment_without_auth_fails() {
     let env = Env::default();
-    let contract_id = env.register(IncrementContract, ());
+    let admin = Address::generate(&env);
+    let contract_id = env.register(IncrementContract, (&admin,));
     let client = IncrementContractClient::new(&env, &contract_id);
 
-    let words = client.increment();
+    client.increment(&Address::generate(&env));
+}
+
+#[test]
+fn test_step_and_max() {
+    let (env, client, admin) = setup();
+    let alice = Address::generate(&env);
+
+    client.set_config(&5, &12);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.config(), Config { step: 5, max: 12 });
+
+    assert_eq!(client.increment(&alice), 5);
+    assert_eq!(client.increment(&alice), 10);
+    assert_eq!(
+        client.try_increment(&alice),
+        Err(Ok(CounterError::AboveMax))
+    );
+    assert_eq!(client.count(&alice), 10);
+
+    assert_eq!(
+        client.try_set_config(&0, &12),
+        Err(Ok(CounterError::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_set_config(&13, &12),
+        Err(Ok(CounterError::InvalidConfig))
+    );
+}
+
+#[test]
+fn test_decrement_and_reset() {
+    let (env, client, _) = setup();
+    let alice = Address::generate(&env);
+    client.set_config(&2, &100);
+
+    client.increment(&alice);
+    client.increment(&alice);
+    assert_eq!(client.decrement(&alice), 2);
+    assert_eq!(client.decrement(&alice), 0);
+    assert_eq!(
+        client.try_decrement(&alice),
+        Err(Ok(CounterError::BelowZero))
+    );
+
+    client.increment(&alice);
+    client.reset(&alice);
+    assert_eq!(client.count(&alice), 0);
+}
+
+#[test]
+fn test_instance_stays_alive_while_used() {
+    let (env, client, admin) = setup();
+    let alice = Address::generate(&env);
+
+    for _ in 0..3 {
+        env.ledger()
+            .with_mut(|ledger| ledger.sequence_number += 20 * 17280);
+        client.increment(&alice);
+    }
+
+    // Sixty days in, the admin and config were bumped along with the counter.
+    assert_eq!(client.admin(), admin);
+    assert_eq!(
+        client.config(),
+        Config {
+            step: 1,
+            max: u32::MAX
+        }
+    );
+    assert_eq!(client.count(&alice), 3);
+}
+
+/// The only event published by the last call, without the contract id.
+fn last_event(env: &Env) -> (Vec<Val>, Val) {
+    let events = env.events().all();
+    assert_eq!(events.len(), 1);
+    let (_, topics, data) = events.get(0).unwrap();
+    (topics, data)
+}
+
+#[test]
+fn test_every_change_emits_an_event() {
+    let (env, client, _) = setup();
+    let alice = Address::generate(&env);
+
+    client.increment(&alice);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("increment"), alice.clone()).into_val(&env),
+                1_u32.into_val(&env),
+            ),
+        ]
+    );
+
+    client.decrement(&alice);
+    let (topics, data) = last_event(&env);
+    assert_eq!(
+        topics,
+        (symbol_short!("decrement"), alice.clone()).into_val(&env)
+    );
+    assert_eq!(u32::from_val(&env, &data), 0);
+
+    client.reset(&alice);
+    let (topics, data) = last_event(&env);
+    assert_eq!(
+        topics,
+        (symbol_short!("reset"), alice.clone()).into_val(&env)
+    );
+    assert_eq!(u32::from_val(&env, &data), 0);
+
+    client.set_config(&3, &9);
+    let (topics, data) = last_event(&env);
+    assert_eq!(topics, (symbol_short!("config"),).into_val(&env));
+    assert_eq!(Config::from_val(&env, &data), Config { step: 3, max: 9 });
 }
@@ -1,3 +1,3 @@
 #![no_std]
 mod test;
-mod todo_list;
+pub mod todo_list;
@@ -1,39 +1,68 @@
 #![cfg(test)]
 
-use crate::todo_list::{Todolist, TodolistClient};
+use crate::todo_list::{
+    Due, LegacyKey, LegacyTodo, Priority, Todo, TodoError, Todolist, TodolistClient, MAX_MIGRATE,
+    MAX_PAGE, MAX_TAGS, MAX_TITLE_LEN, TODOS,
+};
 
-use soroban_sdk::{Env, String};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, AuthorizedFunction, Events, Ledger},
+    vec, Address, Env, FromVal, IntoVal, Map, String, Symbol,
+};
 
 fn setup() -> (Env, TodolistClient<'static>) {
     let env = Env::default();
-    let contract_id = env.register(Todolist, ());
+    env.mock_all_auths();
+    let contract_id = env.register(Todolist, (Address::generate(&env),));
     let client = TodolistClient::new(&env, &contract_id);
 
     (env, client)
 }
 
+/// A medium-priority todo with no due date or tags.
+fn create(
+    env: &Env,
+    client: &TodolistClient,
+    owner: &Address,
+    title: &String,
+    description: &String,
+) -> Todo {
+    client.create_todo(
+        owner,
+        title,
+        description,
+        &Priority::Medium,
+        &Due::None,
+        &vec![env],
+    )
+}
+
 #[test]
 fn test() {
     let (env, client) = setup();
+    let owner = Address::generate(&env);
 
     let title = String::from_str(&env, "Go home!!!");
 
     let description = String::from_str(&env, "From Garage to the hostel");
 
-    let words = client.create_todo(&title, &description);
+    let words = create(&env, &client, &owner, &title, &description);
 
-    let all_todo = client.get_todos_enum();
+    let all_todo = client.list_todos(&owner, &0, &MAX_PAGE);
 
     assert_eq!(all_todo.len(), 1);
     assert_eq!(words.description, description);
     assert_eq!(words.title, title);
     assert_eq!(words.id, 1);
+    assert_eq!(words.owner, owner);
     assert!(!words.status);
 }
 
 #[test]
 fn test_delete() {
     let (env, client) = setup();
+    let owner = Address::generate(&env);
 
     let title = String::from_str(&env, "Go home!!!");
 
@@ -41,15 +70,462 @@ fn test_delete() {
 
     let description = String::from_str(&env, "From Garage to the hostel");
 
-    client.create_todo(&title, &description);
+    create(&env, &client, &owner, &title, &description);
 
-    let all_todo = client.get_todos_enum();
+    let all_todo = client.list_todos(&owner, &0, &MAX_PAGE);
 
     assert_eq!(all_todo.len(), 1);
 
-    client.delete_todo(&id);
+    client.delete_todo(&owner, &id);
 
-    let all_todo = client.get_todos();
+    let all_todo = client.list_todos(&owner, &0, &MAX_PAGE);
 
     assert_eq!(all_todo.len(), 0);
 }
+
+#[test]
+fn test_each_owner_has_their_own_list_and_ids() {
+    let (env, client) = setup();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let title = String::from_str(&env, "Read the Soroban docs");
+    let description = String::from_str(&env, "Storage and auth chapters");
+
+    assert_eq!(create(&env, &client, &alice, &title, &description).id, 1);
+    assert_eq!(create(&env, &client, &alice, &title, &description).id, 2);
+    assert_eq!(create(&env, &client, &bob, &title, &description).id, 1);
+
+    assert_eq!(client.list_todos(&alice, &0, &MAX_PAGE).len(), 2);
+    assert_eq!(client.list_todos(&bob, &0, &MAX_PAGE).len(), 1);
+    assert_eq!(client.next_id(&bob), 2);
+}
+
+#[test]
+fn test_owners_cannot_touch_each_others_todos() {
+    let (env, client) = setup();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let title = String::from_str(&env, "Alice's todo");
+    let description = String::from_str(&env, "Only Alice may change this");
+    create(&env, &client, &alice, &title, &description);
+
+    // Bob signing for himself only reaches his own, empty, list.
+    let new_title = String::from_str(&env, "Bob was here");
+    assert_eq!(
+        client.try_update_todo(&bob, &1, &new_title, &description),
+        Err(Ok(TodoError::NotFound))
+    );
+    assert_eq!(
+        client.try_complete_todo(&bob, &1),
+        Err(Ok(TodoError::NotFound))
+    );
+    assert_eq!(
+        client.try_delete_todo(&bob, &1),
+        Err(Ok(TodoError::NotFound))
+    );
+
+    let todo = client.list_todos(&alice, &0, &MAX_PAGE).get(0).unwrap();
+    assert_eq!(todo.title, title);
+    assert!(!todo.status);
+}
+
+#[test]
+fn test_mutations_require_owner_auth() {
+    let (env, client) = setup();
+    let owner = Address::generate(&env);
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    create(&env, &client, &owner, &title, &description);
+    assert!(client.complete_todo(&owner, &1).status);
+
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    let (signer, invocation) = &auths[0];
+    assert_eq!(signer, &owner);
+    assert_eq!(
+        invocation.function,
+        AuthorizedFunction::Contract((
+            client.address.clone(),
+            Symbol::new(&env, "complete_todo"),
+            vec![&env, owner.into_val(&env), 1_u32.into_val(&env)],
+        ))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_create_without_auth_fails() {
+    let env = Env::default();
+    let contract_id = env.register(Todolist, (Address::generate(&env),));
+    let client = TodolistClient::new(&env, &contract_id);
+
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+    client.create_todo(
+        &Address::generate(&env),
+        &title,
+        &description,
+        &Priority::Medium,
+        &Due::None,
+        &vec![&env],
+    );
+}
+
+#[test]
+fn test_list_todos_pages_by_id() {
+    let (env, client) = setup();
+    let owner = Address::generate(&env);
+    let description = String::from_str(&env, "Paging");
+    for _ in 0..7 {
+        create(
+            &env,
+            &client,
+            &owner,
+            &String::from_str(&env, "Todo"),
+            &description,
+        );
+    }
+    client.delete_todo(&owner, &3);
+
+    let ids = |offset: u32, limit: u32| -> soroban_sdk::Vec<u32> {
+        let mut ids = soroban_sdk::Vec::new(&env);
+        for todo in client.list_todos(&owner, &offset, &limit).iter() {
+            ids.push_back(todo.id);
+        }
+        ids
+    };
+    assert_eq!(ids(0, 3), vec![&env, 1, 2]);
+    assert_eq!(ids(3, 3), vec![&env, 4, 5, 6]);
+    assert_eq!(ids(6, 3), vec![&env, 7]);
+    assert_eq!(ids(9, 3), vec![&env]);
+    assert_eq!(ids(u32::MAX, 3), vec![&env]);
+
+    assert_eq!(client.todo_count(&owner), 6);
+    assert_eq!(client.list_todos(&owner, &0, &MAX_PAGE).len(), 6);
+    assert_eq!(
+        client.try_get_todo(&owner, &3),
+        Err(Ok(TodoError::NotFound))
+    );
+}
+
+#[test]
+fn test_list_todos_limit_is_capped() {
+    let (env, client) = setup();
+    let owner = Address::generate(&env);
+    let text = String::from_str(&env, "Todo");
+    for _ in 0..MAX_PAGE + 5 {
+        create(&env, &client, &owner, &text, &text);
+    }
+
+    assert_eq!(client.list_todos(&owner, &0, &u32::MAX).len(), MAX_PAGE);
+    assert_eq!(client.list_todos(&owner, &MAX_PAGE, &MAX_PAGE).len(), 5);
+}
+
+#[test]
+fn test_todos_stay_alive_while_used() {
+    let (env, client) = setup();
+    let owner = Address::generate(&env);
+    let text = String::from_str(&env, "Keep me");
+    create(&env, &client, &owner, &text, &text);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number += 20 * 17280);
+    client.complete_todo(&owner, &1);
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number += 20 * 17280);
+
+    // Forty days after creation, the todo was bumped by the update.
+    assert!(client.get_todo(&owner, &1).status);
+}
+
+#[test]
+fn test_counters_stay_alive_while_read() {
+    let (env, client) = setup();
+    let owner = Address::generate(&env);
+    let text = String::from_str(&env, "Keep me");
+    create(&env, &client, &owner, &text, &text);
+
+    // Only reads and updates from here on, never a create or delete.
+    for _ in 0..3 {
+        env.ledger()
+            .with_mut(|ledger| ledger.sequence_number += 20 * 17280);
+        assert_eq!(client.list_todos(&owner, &0, &MAX_PAGE).len(), 1);
+        assert_eq!(client.todo_count(&owner), 1);
+        client.complete_todo(&owner, &1);
+    }
+
+    assert_eq!(client.next_id(&owner), 2);
+    assert_eq!(client.todo_count(&owner), 1);
+}
+
+#[test]
+fn test_details_are_stored() {
+    let (env, client) = setup();
+    let owner = Address::generate(&env);
+    let text = String::from_str(&env, "Submit the contract");
+    let tags = vec![&env, symbol_short!("soroban"), symbol_short!("week6")];
+
+    let todo = client.create_todo(
+        &owner,
+        &text,
+        &text,
+        &Priority::High,
+        &Due::Ledger(100),
+        &tags,
+    );
+    assert_eq!(client.get_todo(&owner, &todo.id), todo);
+
+    let updated = client.update_details(&owner, &todo.id, &Priority::Low, &Due::None, &vec![&env]);
+    assert_eq!(client.get_todo(&owner, &todo.id), updated);
+    assert_eq!(updated.priority, Priority::Low);
+    assert_eq!(updated.due, Due::None);
+    assert!(updated.tags.is_empty());
+}
+
+#[test]
+fn test_queries() {
+    let (env, client) = setup();
+    let owner = Address::generate(&env);
+    env.ledger().with_mut(|ledger| {
+        ledger.sequence_number = 10;
+        ledger.timestamp = 1_000;
+    });
+    let text = String::from_str(&env, "Todo");
+    let rust = symbol_short!("rust");
+    let create_due = |due: Due, tags: soroban_sdk::Vec<Symbol>| {
+        client.create_todo(&owner, &text, &text, &Priority::Medium, &due, &tags)
+    };
+
+    create_due(Due::Ledger(20), vec![&env, rust.clone()]); // 1
+    create_due(Due::Timestamp(2_000), vec![&env]); // 2
+    create_due(Due::None, vec![&env, rust.clone()]); // 3
+    create_due(Due::Ledger(15), vec![&env]); // 4
+    client.complete_todo(&owner, &4);
+
+    let ids = |todos: soroban_sdk::Vec<Todo>| {
+        let mut ids = soroban_sdk::Vec::<u32>::new(&env);
+        for todo in todos.iter() {
+            ids.push_back(todo.id);
+        }
+        ids
+    };
+    assert_eq!(ids(client.get_overdue(&owner, &0, &10)), vec![&env]);
+
+    env.ledger().with_mut(|ledger| {
+        ledger.sequence_number = 21;
+        ledger.timestamp = 1_500;
+    });
+    // 4 is past due but already done.
+    assert_eq!(ids(client.get_overdue(&owner, &0, &10)), vec![&env, 1]);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = 2_001);
+    assert_eq!(ids(client.get_overdue(&owner, &0, &10)), vec![&env, 1, 2]);
+
+    assert_eq!(
+        ids(client.get_by_tag(&owner, &rust, &0, &10)),
+        vec![&env, 1, 3]
+    );
+    assert_eq!(
+        ids(client.get_by_tag(&owner, &rust, &1, &10)),
+        vec![&env, 3]
+    );
+    assert_eq!(
+        ids(client.get_by_status(&owner, &true, &0, &10)),
+        vec![&env, 4]
+    );
+    assert_eq!(
+        ids(client.get_by_status(&owner, &false, &0, &10)),
+        vec![&env, 1, 2, 3]
+    );
+}
+
+#[test]
+fn test_events_rebuild_the_list() {
+    let (env, client) = setup();
+    let owner = Address::generate(&env);
+    let text = String::from_str(&env, "Todo");
+    let renamed = String::from_str(&env, "Renamed");
+
+    // Events are only kept for the latest call, so collect them as we go.
+    let mut state: Map<u32, Todo> = Map::new(&env);
+    let replay = |state: &mut Map<u32, Todo>| {
+        for (_, topics, data) in env.events().all().iter() {
+            let action = Symbol::from_val(&env, &topics.get(0).unwrap());
+            assert_eq!(Address::from_val(&env, &topics.get(1).unwrap()), owner);
+            if action == symbol_short!("deleted") {
+                state.remove(u32::from_val(&env, &data));
+            } else {
+                let todo = Todo::from_val(&env, &data);
+                state.set(todo.id, todo);
+            }
+        }
+    };
+
+    for _ in 0..3 {
+        client.create_todo(
+            &owner,
+            &text,
+            &text,
+            &Priority::Low,
+            &Due::None,
+            &vec![&env],
+        );
+        replay(&mut state);
+    }
+    client.update_todo(&owner, &1, &renamed, &text);
+    replay(&mut state);
+    client.update_details(&owner, &2, &Priority::High, &Due::Ledger(5), &vec![&env]);
+    replay(&mut state);
+    client.complete_todo(&owner, &2);
+    replay(&mut state);
+    client.delete_todo(&owner, &3);
+    replay(&mut state);
+
+    assert_eq!(state.values(), client.list_todos(&owner, &0, &MAX_PAGE));
+}
+
+#[test]
+fn test_text_and_tags_are_validated() {
+    let (env, client) = setup();
+    let owner = Address::generate(&env);
+    let empty = String::from_str(&env, "");
+    let text = String::from_str(&env, "Todo");
+    let long_title = String::from_bytes(&env, &[b'a'; MAX_TITLE_LEN as usize + 1]);
+
+    let try_create = |title: &String, tags: &soroban_sdk::Vec<Symbol>| {
+        client.try_create_todo(&owner, title, &text, &Priority::Low, &Due::None, tags)
+    };
+    assert_eq!(
+        try_create(&empty, &vec![&env]),
+        Err(Ok(TodoError::EmptyTitle))
+    );
+    assert_eq!(
+        try_create(&long_title, &vec![&env]),
+        Err(Ok(TodoError::TooLong))
+    );
+
+    let mut tags = vec![&env];
+    for _ in 0..=MAX_TAGS {
+        tags.push_back(symbol_short!("tag"));
+    }
+    assert_eq!(try_create(&text, &tags), Err(Ok(TodoError::TooLong)));
+
+    create(&env, &client, &owner, &text, &text);
+    assert_eq!(
+        client.try_update_todo(&owner, &1, &empty, &text),
+        Err(Ok(TodoError::EmptyTitle))
+    );
+    assert_eq!(client.list_todos(&owner, &0, &MAX_PAGE).len(), 1);
+}
+
+#[test]
+fn test_migrate_legacy_moves_old_todos() {
+    let (env, client) = setup();
+    let admin = client.admin();
+    let owner = Address::generate(&env);
+    let legacy = |id: u32, title: &str, status: bool| LegacyTodo {
+        id,
+        title: String::from_str(&env, title),
+        description: String::from_str(&env, "from before owners"),
+        status,
+    };
+
+    // The owner already has a todo, so migrated ones continue their ids.
+    create(
+        &env,
+        &client,
+        &owner,
+        &String::from_str(&env, "New"),
+        &String::from_str(&env, ""),
+    );
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        storage.set(&TODOS, &vec![&env, legacy(1, "Oldest", true)]);
+        storage.set(
+            &LegacyKey::Todos,
+            &vec![&env, legacy(1, "Old", false), legacy(2, "Older", true)],
+        );
+        storage.set(&LegacyKey::NextID, &3_u32);
+    });
+
+    assert_eq!(client.migrate_legacy(&admin, &owner, &MAX_MIGRATE), 3);
+
+    let todos = client.list_todos(&owner, &0, &MAX_PAGE);
+    assert_eq!(todos.len(), 4);
+    let moved = todos.get(1).unwrap();
+    assert_eq!(moved.id, 2);
+    assert_eq!(moved.title, String::from_str(&env, "Oldest"));
+    assert!(moved.status);
+    assert_eq!(client.next_id(&owner), 5);
+    assert_eq!(client.todo_count(&owner), 4);
+
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&TODOS));
+        assert!(!storage.has(&LegacyKey::Todos));
+        assert!(!storage.has(&LegacyKey::NextID));
+    });
+    // Running it again finds nothing left to move.
+    assert_eq!(client.migrate_legacy(&admin, &owner, &MAX_MIGRATE), 0);
+}
+
+#[test]
+fn test_only_the_admin_migrates() {
+    let (env, client) = setup();
+    let someone = Address::generate(&env);
+    assert_eq!(
+        client.try_migrate_legacy(&someone, &someone, &MAX_MIGRATE),
+        Err(Ok(TodoError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_migrate_legacy_in_batches() {
+    let (env, client) = setup();
+    let admin = client.admin();
+    let owner = Address::generate(&env);
+    env.as_contract(&client.address, || {
+        let mut todos = vec![&env];
+        for id in 1..=45 {
+            todos.push_back(LegacyTodo {
+                id,
+                title: String::from_str(&env, "Old"),
+                description: String::from_str(&env, ""),
+                status: false,
+            });
+        }
+        let storage = env.storage().persistent();
+        storage.set(&TODOS, &vec![&env, todos.get(0).unwrap()]);
+        storage.set(&LegacyKey::Todos, &todos);
+        storage.set(&LegacyKey::NextID, &46_u32);
+    });
+
+    assert_eq!(client.migrate_legacy(&admin, &owner, &0), 0);
+    // The limit is capped, and the oldest key is emptied first.
+    assert_eq!(
+        client.migrate_legacy(&admin, &owner, &u32::MAX),
+        MAX_MIGRATE
+    );
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&TODOS));
+        let left: soroban_sdk::Vec<LegacyTodo> = storage.get(&LegacyKey::Todos).unwrap();
+        assert_eq!(left.len(), 46 - MAX_MIGRATE);
+        assert_eq!(left.get(0).unwrap().id, MAX_MIGRATE);
+        assert!(storage.has(&LegacyKey::NextID));
+    });
+
+    assert_eq!(
+        client.migrate_legacy(&admin, &owner, &MAX_MIGRATE),
+        MAX_MIGRATE
+    );
+    assert_eq!(client.migrate_legacy(&admin, &owner, &MAX_MIGRATE), 6);
+    assert_eq!(client.migrate_legacy(&admin, &owner, &MAX_MIGRATE), 0);
+    assert_eq!(client.todo_count(&owner), 46);
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&LegacyKey::Todos));
+        assert!(!storage.has(&LegacyKey::NextID));
+    });
+}
@@ -1,132 +1,444 @@
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Env, String, Symbol, Vec};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, IntoVal,
+    String, Symbol, Val, Vec,
+};
+
+const DAY_IN_LEDGERS: u32 = 17280;
+const TODO_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const TODO_LIFETIME_THRESHOLD: u32 = TODO_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// The most ids `list_todos` and the queries will look at in one call.
+pub const MAX_PAGE: u32 = 50;
+pub const MAX_TITLE_LEN: u32 = 64;
+pub const MAX_DESCRIPTION_LEN: u32 = 256;
+pub const MAX_TAGS: u32 = 10;
+/// The most legacy todos `migrate_legacy` moves in one call. Each one writes
+/// a new entry, so this keeps a call well inside the write limits.
+pub const MAX_MIGRATE: u32 = 20;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TodoError {
+    /// The owner has no todo with this id.
+    NotFound = 1,
+    /// Only the admin may do this.
+    Unauthorized = 2,
+    EmptyTitle = 3,
+    /// The title, description or tag list is over its limit.
+    TooLong = 4,
+}
+
+#[contracttype]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+/// When a todo is due, either as a ledger sequence number or as a ledger
+/// timestamp in seconds.
+#[contracttype]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Due {
+    None,
+    Ledger(u32),
+    Timestamp(u64),
+}
+
+impl Due {
+    fn has_passed(&self, env: &Env) -> bool {
+        match *self {
+            Due::None => false,
+            Due::Ledger(sequence) => env.ledger().sequence() > sequence,
+            Due::Timestamp(timestamp) => env.ledger().timestamp() > timestamp,
+        }
+    }
+}
 
 #[contracttype]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Todo {
     pub id: u32,
+    pub owner: Address,
     pub title: String,
     pub description: String,
     pub status: bool,
+    pub priority: Priority,
+    pub due: Due,
+    pub tags: Vec<Symbol>,
+}
+
+impl Todo {
+    /// Not done, and past its due ledger or time.
+    pub fn is_overdue(&self, env: &Env) -> bool {
+        !self.status && self.due.has_passed(env)
+    }
 }
 
+/// Every owner has their own id sequence, so ids start at 1 for each user.
+/// Each todo is its own entry, so a call only touches the todos it needs.
+///
+/// Every change is also published as an event with topics `(action, owner)`:
+/// `created`, `updated` and `completed` carry the whole [`Todo`] as it is
+/// afterwards, and `deleted` carries the id. Replaying them in order
+/// rebuilds each owner's list.
 #[contracttype]
 enum DataKey {
+    Admin,
+    Todo(Address, u32),
+    NextID(Address),
+    Count(Address),
+}
+
+/// Keys used before todos had owners, when every todo lived in one `Vec`.
+/// The variant names match the old `DataKey`, so they map to the same
+/// entries.
+#[contracttype]
+pub(crate) enum LegacyKey {
     Todos,
     NextID,
 }
 
-const TODOS: Symbol = symbol_short!("TOD0S");
+/// The oldest key of all. Nothing writes it any more.
+pub(crate) const TODOS: Symbol = symbol_short!("TOD0S");
 
-const NEXT_ID: Symbol = symbol_short!("NEXT_ID");
+/// A todo as it was stored under the legacy keys.
+#[contracttype]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct LegacyTodo {
+    pub id: u32,
+    pub title: String,
+    pub description: String,
+    pub status: bool,
+}
 
 #[contract]
 pub struct Todolist;
 
 #[contractimpl]
 impl Todolist {
-    pub fn create_todo(env: Env, title: String, description: String) -> Todo {
-        let mut todos = Self::get_todos_enum(&env);
+    /// `admin` may run `migrate_legacy`.
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
 
-        let mut current_id = Self::get_id_enum(&env);
+    pub fn create_todo(
+        env: Env,
+        owner: Address,
+        title: String,
+        description: String,
+        priority: Priority,
+        due: Due,
+        tags: Vec<Symbol>,
+    ) -> Result<Todo, TodoError> {
+        owner.require_auth();
+        Self::check_text(&title, &description)?;
+        Self::check_tags(&tags)?;
 
-        let todo = Todo {
-            id: current_id,
-            title,
-            description,
-            status: false,
-        };
+        let todo = Self::insert(&env, owner, title, description, false, priority, due, tags);
+        Ok(todo)
+    }
 
-        todos.push_back(todo.clone());
+    pub fn update_todo(
+        env: Env,
+        owner: Address,
+        id: u32,
+        title: String,
+        description: String,
+    ) -> Result<Todo, TodoError> {
+        owner.require_auth();
+        Self::check_text(&title, &description)?;
 
-        env.storage().persistent().set(&DataKey::Todos, &todos);
+        let mut todo = Self::get_todo(env.clone(), owner, id)?;
+        todo.title = title;
+        todo.description = description;
+        Self::save(&env, &todo);
+        Self::publish(&env, symbol_short!("updated"), &todo);
+        Ok(todo)
+    }
 
-        current_id += 1;
+    /// Replaces the priority, due date and tags of a todo.
+    pub fn update_details(
+        env: Env,
+        owner: Address,
+        id: u32,
+        priority: Priority,
+        due: Due,
+        tags: Vec<Symbol>,
+    ) -> Result<Todo, TodoError> {
+        owner.require_auth();
+        Self::check_tags(&tags)?;
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::NextID, &current_id);
+        let mut todo = Self::get_todo(env.clone(), owner, id)?;
+        todo.priority = priority;
+        todo.due = due;
+        todo.tags = tags;
+        Self::save(&env, &todo);
+        Self::publish(&env, symbol_short!("updated"), &todo);
+        Ok(todo)
+    }
 
-        todo
+    /// Flips a todo between done and not done.
+    pub fn complete_todo(env: Env, owner: Address, id: u32) -> Result<Todo, TodoError> {
+        owner.require_auth();
+
+        let mut todo = Self::get_todo(env.clone(), owner, id)?;
+        todo.status = !todo.status;
+        Self::save(&env, &todo);
+        Self::publish(&env, symbol_short!("completed"), &todo);
+        Ok(todo)
     }
 
-    pub fn update_todo(env: Env, id: u32, title: String, description: String) -> bool {
-        let mut todos = Self::get_todos_enum(&env);
-        for i in 0..todos.len() {
-            let mut updated = todos.get(i).unwrap();
-            if updated.id == id {
-                updated.title = title;
-                updated.description = description;
-                todos.set(i, updated);
-                env.storage().persistent().set(&DataKey::Todos, &todos);
-                return true;
-            }
+    pub fn delete_todo(env: Env, owner: Address, id: u32) -> Result<(), TodoError> {
+        owner.require_auth();
+        let key = DataKey::Todo(owner.clone(), id);
+        if !env.storage().persistent().has(&key) {
+            return Err(TodoError::NotFound);
         }
-        false
+
+        env.storage().persistent().remove(&key);
+        let count = Self::todo_count(env.clone(), owner.clone());
+        Self::set_counter(&env, DataKey::Count(owner.clone()), count - 1);
+        env.events().publish((symbol_short!("deleted"), owner), id);
+        Ok(())
     }
 
-    pub fn complete_todo(env: Env, id: u32) -> bool {
-        let mut todos = Self::get_todos_enum(&env);
+    pub fn get_todo(env: Env, owner: Address, id: u32) -> Result<Todo, TodoError> {
+        Self::load(&env, &owner, id).ok_or(TodoError::NotFound)
+    }
 
-        for i in 0..todos.len() {
-            if let Some(mut todo) = todos.get(i) {
-                if todo.id == id {
-                    todo.status = !todo.status;
-                    todos.set(i, todo);
-                    env.storage().persistent().set(&DataKey::Todos, &todos);
-                    return true;
-                }
-            }
+    /// One page of `owner`'s todos, in id order.
+    ///
+    /// `offset` and `limit` count ids rather than todos: the page holds the
+    /// todos with ids from `offset + 1` to `offset + limit` that still exist,
+    /// so it can come back short after deletes. The next page starts at
+    /// `offset + limit`. `limit` is capped at [`MAX_PAGE`].
+    pub fn list_todos(env: Env, owner: Address, offset: u32, limit: u32) -> Vec<Todo> {
+        Self::page(&env, &owner, offset, limit, |_| true)
+    }
+
+    /// The overdue todos in one page of ids, see `list_todos`.
+    pub fn get_overdue(env: Env, owner: Address, offset: u32, limit: u32) -> Vec<Todo> {
+        Self::page(&env, &owner, offset, limit, |todo| todo.is_overdue(&env))
+    }
+
+    /// The todos tagged `tag` in one page of ids, see `list_todos`.
+    pub fn get_by_tag(env: Env, owner: Address, tag: Symbol, offset: u32, limit: u32) -> Vec<Todo> {
+        Self::page(&env, &owner, offset, limit, |todo| todo.tags.contains(&tag))
+    }
+
+    /// The done (`status` true) or open todos in one page of ids, see `list_todos`.
+    pub fn get_by_status(
+        env: Env,
+        owner: Address,
+        status: bool,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<Todo> {
+        Self::page(&env, &owner, offset, limit, |todo| todo.status == status)
+    }
+
+    /// The id the owner's next todo will get.
+    pub fn next_id(env: Env, owner: Address) -> u32 {
+        Self::counter(&env, DataKey::NextID(owner)).unwrap_or(1)
+    }
+
+    /// How many todos `owner` has right now.
+    pub fn todo_count(env: Env, owner: Address) -> u32 {
+        Self::counter(&env, DataKey::Count(owner)).unwrap_or(0)
+    }
+
+    pub fn admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    /// Moves up to `limit` todos still under the legacy keys to `owner`,
+    /// giving them fresh ids, and removes them from the legacy entries.
+    /// Returns how many moved; call again until it returns 0. `limit` is
+    /// capped at [`MAX_MIGRATE`].
+    ///
+    /// Legacy todos have no owner, so only the admin set by the constructor
+    /// may decide who gets them. Each moved todo is announced with a
+    /// `created` event.
+    ///
+    /// This only runs on an instance deployed from this code whose storage
+    /// also holds legacy entries. The older versions that wrote those keys
+    /// have no `upgrade` entry point, so an instance deployed from them can
+    /// never reach this; its todos have to be read off-chain and recreated
+    /// with `create_todo`.
+    pub fn migrate_legacy(
+        env: Env,
+        admin: Address,
+        owner: Address,
+        limit: u32,
+    ) -> Result<u32, TodoError> {
+        admin.require_auth();
+        if admin != Self::admin(env.clone()) {
+            return Err(TodoError::Unauthorized);
         }
-        false
+
+        let mut left = limit.min(MAX_MIGRATE);
+        left -= Self::migrate_from(&env, &TODOS, &owner, left);
+        left -= Self::migrate_from(&env, &LegacyKey::Todos, &owner, left);
+
+        let storage = env.storage().persistent();
+        if !storage.has(&TODOS) && !storage.has(&LegacyKey::Todos) {
+            storage.remove(&LegacyKey::NextID);
+        }
+        Ok(limit.min(MAX_MIGRATE) - left)
     }
+}
 
-    pub fn delete_todo(env: Env, id: u32) -> bool {
-        let mut todos = Self::get_todos_enum(&env);
+impl Todolist {
+    /// Moves up to `limit` todos from the front of the legacy list under
+    /// `key` to `owner`, writing back the rest or removing the key once it is
+    /// empty. Returns how many moved.
+    fn migrate_from<K>(env: &Env, key: &K, owner: &Address, limit: u32) -> u32
+    where
+        K: IntoVal<Env, Val>,
+    {
+        let storage = env.storage().persistent();
+        let Some(mut legacy) = storage.get::<K, Vec<LegacyTodo>>(key) else {
+            return 0;
+        };
 
-        if let Some(todo) = todos.iter().position(|i| i.id == id) {
-            todos.remove(todo as u32);
-            env.storage().persistent().set(&DataKey::Todos, &todos);
-            return true;
+        let moved = limit.min(legacy.len());
+        for old in legacy.slice(..moved).iter() {
+            Self::insert(
+                env,
+                owner.clone(),
+                old.title,
+                old.description,
+                old.status,
+                Priority::Medium,
+                Due::None,
+                Vec::new(env),
+            );
         }
 
-        false
+        legacy = legacy.slice(moved..);
+        if legacy.is_empty() {
+            storage.remove(key);
+        } else {
+            storage.set(key, &legacy);
+        }
+        moved
+    }
+
+    /// Stores a new todo under the owner's next id and announces it.
+    #[allow(clippy::too_many_arguments)]
+    fn insert(
+        env: &Env,
+        owner: Address,
+        title: String,
+        description: String,
+        status: bool,
+        priority: Priority,
+        due: Due,
+        tags: Vec<Symbol>,
+    ) -> Todo {
+        let id = Self::next_id(env.clone(), owner.clone());
+        let todo = Todo {
+            id,
+            owner: owner.clone(),
+            title,
+            description,
+            status,
+            priority,
+            due,
+            tags,
+        };
+
+        Self::save(env, &todo);
+        Self::publish(env, symbol_short!("created"), &todo);
+
+        Self::set_counter(env, DataKey::NextID(owner.clone()), id + 1);
+        let count = Self::todo_count(env.clone(), owner.clone());
+        Self::set_counter(env, DataKey::Count(owner), count + 1);
+        todo
+    }
+
+    fn check_text(title: &String, description: &String) -> Result<(), TodoError> {
+        if title.is_empty() {
+            return Err(TodoError::EmptyTitle);
+        }
+        if title.len() > MAX_TITLE_LEN || description.len() > MAX_DESCRIPTION_LEN {
+            return Err(TodoError::TooLong);
+        }
+        Ok(())
     }
 
-    pub fn update_todo2(env: Env, id: u32, title: String, description: String) -> bool {
-        let mut todos = Self::get_todos_enum(&env);
+    fn check_tags(tags: &Vec<Symbol>) -> Result<(), TodoError> {
+        if tags.len() > MAX_TAGS {
+            return Err(TodoError::TooLong);
+        }
+        Ok(())
+    }
 
-        for i in 0..todos.len() {
-            if let Some(mut todo) = todos.get(i) {
-                if todo.id == id {
-                    todo.title = title;
-                    todo.description = description;
-                    todos.set(i, todo);
-                    env.storage().persistent().set(&DataKey::Todos, &todos);
+    /// The todos with ids from `offset + 1` to `offset + limit` that exist
+    /// and match `keep`.
+    fn page(
+        env: &Env,
+        owner: &Address,
+        offset: u32,
+        limit: u32,
+        keep: impl Fn(&Todo) -> bool,
+    ) -> Vec<Todo> {
+        let next_id = Self::next_id(env.clone(), owner.clone());
+        let first = offset.saturating_add(1);
+        let end = first.saturating_add(limit.min(MAX_PAGE)).min(next_id);
 
-                    return true;
-                }
+        let mut todos = Vec::new(env);
+        for id in first..end {
+            match Self::load(env, owner, id) {
+                Some(todo) if keep(&todo) => todos.push_back(todo),
+                _ => {}
             }
         }
-        false
+        todos
     }
 
-    pub fn get_todos(env: &Env) -> Vec<Todo> {
-        env.storage()
-            .persistent()
-            .get(&TODOS)
-            .unwrap_or(Vec::new(env))
+    fn publish(env: &Env, action: Symbol, todo: &Todo) {
+        env.events()
+            .publish((action, todo.owner.clone()), todo.clone());
     }
 
-    pub fn get_todos_enum(env: &Env) -> Vec<Todo> {
-        env.storage()
-            .persistent()
-            .get(&DataKey::Todos)
-            .unwrap_or(Vec::new(env))
+    /// Reads a todo, keeping it alive while it is in use.
+    fn load(env: &Env, owner: &Address, id: u32) -> Option<Todo> {
+        let key = DataKey::Todo(owner.clone(), id);
+        let todo = env.storage().persistent().get(&key)?;
+        Self::bump(env, &key);
+        Some(todo)
     }
-    pub fn get_id_enum(env: &Env) -> u32 {
+
+    fn save(env: &Env, todo: &Todo) {
+        let key = DataKey::Todo(todo.owner.clone(), todo.id);
+        env.storage().persistent().set(&key, todo);
+        Self::bump(env, &key);
+    }
+
+    /// Reads a counter, keeping it alive as long as the todos that use it.
+    fn counter(env: &Env, key: DataKey) -> Option<u32> {
+        let value = env.storage().persistent().get(&key)?;
+        Self::bump(env, &key);
+        Some(value)
+    }
+
+    fn set_counter(env: &Env, key: DataKey, value: u32) {
+        env.storage().persistent().set(&key, &value);
+        Self::bump(env, &key);
+    }
+
+    /// Extends `key` and the contract instance, so neither is archived
+    /// while the todo list is in use.
+    fn bump(env: &Env, key: &DataKey) {
         env.storage()
             .persistent()
-            .get(&DataKey::NextID)
-            .unwrap_or(1)
+            .extend_ttl(key, TODO_LIFETIME_THRESHOLD, TODO_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .extend_ttl(TODO_LIFETIME_THRESHOLD, TODO_BUMP_AMOUNT);
     }
 }
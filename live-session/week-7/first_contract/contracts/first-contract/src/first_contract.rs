@@ -1,3 +1,4 @@
+use crate::error::ContractErrors;
 use soroban_sdk::{contract, contractimpl};
 
 #[contract]
@@ -5,11 +6,11 @@ pub struct FirstContract;
 
 #[contractimpl]
 impl FirstContract {
-    pub fn add(a: i32, b: i32) -> i32 {
-        a + b
+    pub fn add(a: i32, b: i32) -> Result<i32, ContractErrors> {
+        a.checked_add(b).ok_or(ContractErrors::Overflow)
     }
 
-    pub fn sub(a: i32, b: i32) -> i32 {
-        a - b
+    pub fn sub(a: i32, b: i32) -> Result<i32, ContractErrors> {
+        a.checked_sub(b).ok_or(ContractErrors::Overflow)
     }
 }
@@ -1,4 +1,5 @@
 #![no_std]
 
-mod first_contract;
+pub mod error;
+pub mod first_contract;
 mod test;
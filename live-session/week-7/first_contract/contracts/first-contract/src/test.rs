@@ -1,13 +1,32 @@
 #![cfg(test)]
 
+use crate::error::ContractErrors;
 use crate::first_contract::{FirstContract, FirstContractClient};
 
-use super::*;
-use soroban_sdk::{vec, Env, String};
+use soroban_sdk::Env;
 
 #[test]
-fn test() {
+fn test_add_and_sub() {
     let env = Env::default();
     let contract_id = env.register(FirstContract, ());
     let client = FirstContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.add(&2, &3), 5);
+    assert_eq!(client.sub(&2, &3), -1);
+}
+
+#[test]
+fn test_overflow_is_an_error() {
+    let env = Env::default();
+    let contract_id = env.register(FirstContract, ());
+    let client = FirstContractClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client.try_add(&i32::MAX, &1),
+        Err(Ok(ContractErrors::Overflow))
+    );
+    assert_eq!(
+        client.try_sub(&i32::MIN, &1),
+        Err(Ok(ContractErrors::Overflow))
+    );
 }
@@ -1,8 +1,14 @@
 use soroban_sdk::contracterror;
 
 #[contracterror]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u32)]
 pub enum ContractErrors {
     LessThan = 1,
+    /// The first contract reported that the result does not fit in an `i32`.
+    Overflow = 2,
+    /// The call into the first contract panicked or could not be invoked.
+    CalleeFailed = 3,
+    /// The first contract returned a value that is not an `i32`.
+    InvalidReturn = 4,
 }
@@ -1,5 +1,5 @@
 #![no_std]
-mod error;
+pub mod error;
 mod import;
-mod second_contract;
+pub mod second_contract;
 mod test;
@@ -1,6 +1,6 @@
 use crate::error::ContractErrors;
-use crate::import::contract_a::Client;
-use soroban_sdk::{contract, contractimpl};
+use crate::import::contract_a::{Client, ContractErrors as FirstContractErrors};
+use soroban_sdk::{contract, contractimpl, ConversionError, InvokeError};
 
 #[contract]
 pub struct SecondContract;
@@ -12,15 +12,14 @@ impl SecondContract {
         contract_address: soroban_sdk::Address,
         a: i32,
         b: i32,
-    ) -> Result<(), ContractErrors> {
+    ) -> Result<i32, ContractErrors> {
         if a < 50 {
             return Err(ContractErrors::LessThan);
         }
 
         let new = Client::new(&env, &contract_address);
 
-        new.add(&a, &b);
-        Ok(())
+        Self::map_result(new.try_add(&a, &b))
     }
 
     pub fn sub_from_first_contract(
@@ -35,6 +34,19 @@ impl SecondContract {
 
         let new = Client::new(&env, &contract_address);
 
-        Ok(new.sub(&a, &b))
+        Self::map_result(new.try_sub(&a, &b))
+    }
+}
+
+impl SecondContract {
+    fn map_result(
+        result: Result<Result<i32, ConversionError>, Result<FirstContractErrors, InvokeError>>,
+    ) -> Result<i32, ContractErrors> {
+        match result {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(_)) => Err(ContractErrors::InvalidReturn),
+            Err(Ok(FirstContractErrors::Overflow)) => Err(ContractErrors::Overflow),
+            Err(Err(_)) => Err(ContractErrors::CalleeFailed),
+        }
     }
 }
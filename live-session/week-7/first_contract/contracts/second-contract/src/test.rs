@@ -1,13 +1,61 @@
 #![cfg(test)]
 
+use crate::error::ContractErrors;
+use crate::import::contract_a;
 use crate::second_contract::{SecondContract, SecondContractClient};
 
-use super::*;
-use soroban_sdk::{vec, Env, String};
+use soroban_sdk::{Address, Env};
+
+fn setup(env: &Env) -> (SecondContractClient<'_>, Address) {
+    let first_id = env.register(contract_a::WASM, ());
+    let second_id = env.register(SecondContract, ());
+    (SecondContractClient::new(env, &second_id), first_id)
+}
+
+#[test]
+fn test_returns_callee_result() {
+    let env = Env::default();
+    let (client, first_id) = setup(&env);
+
+    assert_eq!(client.add_from_first_contract(&first_id, &60, &5), 65);
+    assert_eq!(client.sub_from_first_contract(&first_id, &60, &5), 55);
+}
+
+#[test]
+fn test_less_than_is_checked_before_the_call() {
+    let env = Env::default();
+    let (client, first_id) = setup(&env);
+
+    assert_eq!(
+        client.try_add_from_first_contract(&first_id, &10, &5),
+        Err(Ok(ContractErrors::LessThan))
+    );
+}
 
 #[test]
-fn test() {
+fn test_callee_overflow_is_propagated() {
     let env = Env::default();
-    let contract_id = env.register(SecondContract, ());
-    let client = SecondContractClient::new(&env, &contract_id);
+    let (client, first_id) = setup(&env);
+
+    assert_eq!(
+        client.try_add_from_first_contract(&first_id, &i32::MAX, &1),
+        Err(Ok(ContractErrors::Overflow))
+    );
+    assert_eq!(
+        client.try_sub_from_first_contract(&first_id, &50, &i32::MIN),
+        Err(Ok(ContractErrors::Overflow))
+    );
+}
+
+#[test]
+fn test_callee_failure_is_mapped() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    // The second contract has no `add`, so invoking it as the first fails.
+    let not_first = client.address.clone();
+    assert_eq!(
+        client.try_add_from_first_contract(&not_first, &60, &5),
+        Err(Ok(ContractErrors::CalleeFailed))
+    );
 }